@@ -17,7 +17,8 @@ use crate::{
 use crate::{error::{handle_error, Error}};
 
 // Escrow integration constants
-const ESCROW_RESOLVE_DISPUTE: &str = "resolve_dispute";
+const ESCROW_RESOLVE_DISPUTE: &str = "resolve_dispute_by_id";
+const ESCROW_RESOLVE_MILESTONE_DISPUTE: &str = "resolve_milestone_dispute_by_id";
 const ESCROW_CLIENT_WINS: &str = "client_wins";
 const ESCROW_FREELANCER_WINS: &str = "freelancer_wins";
 const ESCROW_SPLIT: &str = "split";
@@ -82,12 +83,15 @@ pub fn initialize(
     );
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn open_dispute(
     env: &Env,
     job_id: u32,
     initiator: Address,
     reason: String,
     escrow_contract: Option<Address>,
+    escrow_id: u32,
+    milestone_id: Option<u32>,
     dispute_amount: i128,
 ) {
     initiator.require_auth();
@@ -115,6 +119,11 @@ pub fn open_dispute(
         }
     }
 
+    // A milestone can only be contested through the escrow that holds it
+    if milestone_id.is_some() && escrow_contract.is_none() {
+        handle_error(env, Error::EscrowIntegrationFailed);
+    }
+
     let mut disputes: Map<u32, DisputeData> = env.storage().instance().get(&DISPUTES).unwrap();
 
     if disputes.contains_key(job_id) {
@@ -138,6 +147,8 @@ pub fn open_dispute(
         dispute_amount,
         fee_collected: 0,
         escrow_contract,
+        escrow_id,
+        milestone_id,
         timeout_timestamp: Some(timeout_timestamp),
         evidence: Vec::new(env),
         mediator: None,
@@ -156,6 +167,36 @@ pub fn open_dispute(
     );
 }
 
+/// Forward the ruling to the escrow contract, addressing the disputed escrow by its
+/// id in the escrow's registry. Milestone-scoped disputes only settle the contested
+/// milestone; the rest of the escrow is left untouched.
+fn settle_escrow(env: &Env, dispute: &DisputeData, caller: Address, decision: DisputeOutcome) {
+    let escrow_contract = match dispute.escrow_contract.clone() {
+        Some(escrow_contract) => escrow_contract,
+        None => return,
+    };
+
+    let escrow_result = match decision {
+        DisputeOutcome::FavorClient => ESCROW_CLIENT_WINS,
+        DisputeOutcome::FavorFreelancer => ESCROW_FREELANCER_WINS,
+        DisputeOutcome::Split => ESCROW_SPLIT,
+        DisputeOutcome::None => handle_error(env, Error::InvalidOutcome),
+    };
+
+    match dispute.milestone_id {
+        Some(milestone_id) => env.invoke_contract::<()>(
+            &escrow_contract,
+            &Symbol::new(env, ESCROW_RESOLVE_MILESTONE_DISPUTE),
+            (dispute.escrow_id, caller, milestone_id, Symbol::new(env, escrow_result)).into_val(env),
+        ),
+        None => env.invoke_contract::<()>(
+            &escrow_contract,
+            &Symbol::new(env, ESCROW_RESOLVE_DISPUTE),
+            (dispute.escrow_id, caller, Symbol::new(env, escrow_result)).into_val(env),
+        ),
+    }
+}
+
 pub fn get_dispute(env: &Env, job_id: u32) -> DisputeData {
     if !env.storage().instance().has(&ARBITRATOR) {
         handle_error(env, Error::NotInitialized);
@@ -339,27 +380,14 @@ pub fn resolve_dispute(env: &Env, job_id: u32, decision: DisputeOutcome) {
     dispute.resolution_timestamp = Some(env.ledger().timestamp());
 
     // Integrate with escrow contract if available
-    if let Some(escrow_contract) = dispute.escrow_contract.clone() {
-        let escrow_result = match decision {
-            DisputeOutcome::FavorClient => ESCROW_CLIENT_WINS,
-            DisputeOutcome::FavorFreelancer => ESCROW_FREELANCER_WINS,
-            DisputeOutcome::Split => ESCROW_SPLIT,
-            DisputeOutcome::None => handle_error(env, Error::InvalidOutcome),
-        };
-
-        // Call the escrow contract to resolve the dispute
+    if dispute.escrow_contract.is_some() {
         // Note: In a production environment, you would need to pass the authorized caller
         // For now, we'll use the assigned mediator/arbitrator as the caller
         let escrow_caller = match dispute.level {
             DisputeLevel::Mediation => dispute.mediator.clone().unwrap(),
             DisputeLevel::Arbitration => dispute.arbitrator.clone().unwrap(),
         };
-
-        env.invoke_contract::<()>(
-            &escrow_contract,
-            &Symbol::new(env, ESCROW_RESOLVE_DISPUTE),
-            (escrow_caller, Symbol::new(env, escrow_result)).into_val(env),
-        );
+        settle_escrow(env, &dispute, escrow_caller, decision);
     }
 
     disputes.set(job_id, dispute);
@@ -448,21 +476,7 @@ pub fn resolve_dispute_with_auth(
     dispute.resolution_timestamp = Some(env.ledger().timestamp());
 
     // Integrate with escrow contract if available
-    if let Some(escrow_contract) = dispute.escrow_contract.clone() {
-        let escrow_result = match decision {
-            DisputeOutcome::FavorClient => ESCROW_CLIENT_WINS,
-            DisputeOutcome::FavorFreelancer => ESCROW_FREELANCER_WINS,
-            DisputeOutcome::Split => ESCROW_SPLIT,
-            DisputeOutcome::None => handle_error(env, Error::InvalidOutcome),
-        };
-
-        // Call the escrow contract to resolve the dispute
-        env.invoke_contract::<()>(
-            &escrow_contract,
-            &Symbol::new(env, ESCROW_RESOLVE_DISPUTE),
-            (caller, Symbol::new(env, escrow_result)).into_val(env),
-        );
-    }
+    settle_escrow(env, &dispute, caller, decision);
 
     disputes.set(job_id, dispute);
     env.storage().instance().set(&DISPUTES, &disputes);
@@ -794,6 +808,8 @@ pub fn get_dispute_info(env: &Env,  dispute_id: u32) -> Result<DisputeInfo, Erro
         dispute_amount: dispute.dispute_amount,
         fee_collected: dispute.fee_collected,
         escrow_contract: dispute.escrow_contract,
+        escrow_id: dispute.escrow_id,
+        milestone_id: dispute.milestone_id,
         timeout_timestamp: dispute.timeout_timestamp,
         evidence: evidences,
         mediator: dispute.mediator,
@@ -38,6 +38,7 @@ impl DisputeResolutionContract {
         initiator: Address,
         reason: String,
         escrow_contract: Option<Address>,
+        escrow_id: u32,
         dispute_amount: i128,
     ) -> Result<(), Error> {
         contract::open_dispute(
@@ -46,6 +47,33 @@ impl DisputeResolutionContract {
             initiator,
             reason,
             escrow_contract,
+            escrow_id,
+            None,
+            dispute_amount,
+        );
+        Ok(())
+    }
+
+    // Contest a single escrow milestone; the ruling only settles that milestone's amount
+    #[allow(clippy::too_many_arguments)]
+    pub fn open_milestone_dispute(
+        env: Env,
+        job_id: u32,
+        initiator: Address,
+        reason: String,
+        escrow_contract: Address,
+        escrow_id: u32,
+        milestone_id: u32,
+        dispute_amount: i128,
+    ) -> Result<(), Error> {
+        contract::open_dispute(
+            &env,
+            job_id,
+            initiator,
+            reason,
+            Some(escrow_contract),
+            escrow_id,
+            Some(milestone_id),
             dispute_amount,
         );
         Ok(())
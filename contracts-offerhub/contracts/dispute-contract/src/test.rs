@@ -5,7 +5,8 @@ use crate::{
     DisputeResolutionContract, DisputeResolutionContractClient,
 };
 use soroban_sdk::{
-    log, testutils::{Address as _, Ledger}, Address, Env, String
+    contract, contractimpl, log, symbol_short, testutils::{Address as _, Ledger}, Address, Env,
+    String, Symbol,
 };
 
 fn setup_env() -> Env {
@@ -71,6 +72,7 @@ fn test_open_dispute() {
         &initiator,
         &reason,
         &escrow_contract,
+        &0,
         &dispute_amount,
     );
 
@@ -85,6 +87,7 @@ fn test_open_dispute() {
     assert_eq!(dispute.dispute_amount, dispute_amount);
     assert_eq!(dispute.fee_collected, 0);
     assert_eq!(dispute.escrow_contract, escrow_contract);
+    assert!(dispute.milestone_id.is_none());
     assert!(dispute.timeout_timestamp.is_some());
     assert_eq!(dispute.evidence.len(), 0);
     assert!(dispute.mediator.is_none());
@@ -92,6 +95,79 @@ fn test_open_dispute() {
     assert!(dispute.resolution_timestamp.is_none());
 }
 
+// Stands in for the escrow contract and records the last ruling it was sent
+#[contract]
+pub struct MockEscrow;
+
+#[contractimpl]
+impl MockEscrow {
+    pub fn resolve_dispute_by_id(env: Env, escrow_id: u32, _caller: Address, result: Symbol) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("settled"), &(escrow_id, None::<u32>, result));
+    }
+
+    pub fn resolve_milestone_dispute_by_id(
+        env: Env,
+        escrow_id: u32,
+        _caller: Address,
+        milestone_id: u32,
+        result: Symbol,
+    ) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("settled"), &(escrow_id, Some(milestone_id), result));
+    }
+
+    pub fn settled(env: Env) -> (u32, Option<u32>, Symbol) {
+        env.storage().instance().get(&symbol_short!("settled")).unwrap()
+    }
+}
+
+#[test]
+fn test_ruling_settles_the_disputed_registry_escrow() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let (client, admin, _, _) = create_contract(&env);
+    let escrow = MockEscrowClient::new(&env, &env.register(MockEscrow, ()));
+    let initiator = Address::generate(&env);
+    let mediator = Address::generate(&env);
+    let reason = String::from_str(&env, "Job not completed");
+    client.add_mediator_access(&admin, &mediator);
+
+    client.open_dispute(&1, &initiator, &reason, &Some(escrow.address.clone()), &5, &1000);
+    client.assign_mediator(&1, &admin, &mediator);
+    client.resolve_dispute(&1, &DisputeOutcome::FavorClient);
+    assert_eq!(escrow.settled(), (5, None, Symbol::new(&env, "client_wins")));
+
+    client.open_milestone_dispute(&2, &initiator, &reason, &escrow.address, &3, &2, &400);
+    client.assign_mediator(&2, &admin, &mediator);
+    client.resolve_dispute(&2, &DisputeOutcome::FavorFreelancer);
+    assert_eq!(escrow.settled(), (3, Some(2), Symbol::new(&env, "freelancer_wins")));
+    assert_eq!(client.get_dispute(&2).escrow_id, 3);
+}
+
+#[test]
+fn test_open_milestone_dispute() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let (client, _, _, _) = create_contract(&env);
+    let initiator = Address::generate(&env);
+    let job_id = 1;
+    let reason = String::from_str(&env, "Milestone 2 not delivered");
+    let escrow_contract = Address::generate(&env);
+
+    client.open_milestone_dispute(&job_id, &initiator, &reason, &escrow_contract, &0, &2, &400);
+
+    let dispute = client.get_dispute(&job_id);
+    assert_eq!(dispute.escrow_contract, Some(escrow_contract));
+    assert_eq!(dispute.milestone_id, Some(2));
+    assert_eq!(dispute.dispute_amount, 400);
+    assert_eq!(dispute.state, DisputeState::Open);
+}
+
 #[test]
 #[should_panic(expected = "HostError: Error(Contract, #4)")]
 fn test_open_dispute_already_exists() {
@@ -110,6 +186,7 @@ fn test_open_dispute_already_exists() {
         &initiator,
         &reason,
         &escrow_contract,
+        &0,
         &dispute_amount,
     );
 
@@ -119,6 +196,7 @@ fn test_open_dispute_already_exists() {
         &initiator,
         &reason,
         &escrow_contract,
+        &0,
         &dispute_amount,
     );
 }
@@ -142,6 +220,7 @@ fn test_add_evidence() {
         &initiator,
         &reason,
         &escrow_contract,
+        &0,
         &dispute_amount,
     );
 
@@ -223,6 +302,7 @@ fn test_assign_mediator() {
         &initiator,
         &reason,
         &escrow_contract,
+        &0,
         &dispute_amount,
     );
 
@@ -258,6 +338,7 @@ fn test_escalate_to_arbitration() {
         &initiator,
         &reason,
         &escrow_contract,
+        &0,
         &dispute_amount,
     );
     client.assign_mediator(&job_id, &admin, &mediator);
@@ -293,6 +374,7 @@ fn test_resolve_dispute() {
         &initiator,
         &reason,
         &escrow_contract,
+        &0,
         &dispute_amount,
     );
 
@@ -333,6 +415,7 @@ fn test_resolve_dispute_already_resolved() {
         &initiator,
         &reason,
         &escrow_contract,
+        &0,
         &dispute_amount,
     );
 
@@ -389,6 +472,7 @@ fn test_check_timeout() {
         &initiator,
         &reason,
         &escrow_contract,
+        &0,
         &dispute_amount,
     );
 
@@ -455,6 +539,7 @@ fn test_get_total_disputes() {
         &initiator,
         &reason,
         &escrow_contract_addr,
+        &0,
         &dispute_amount,
     );
     let count_after_first = client.get_total_disputes();
@@ -466,6 +551,7 @@ fn test_get_total_disputes() {
         &initiator,
         &reason,
         &escrow_contract_addr,
+        &0,
         &dispute_amount,
     );
     let count_after_second = client.get_total_disputes();
@@ -495,6 +581,7 @@ fn test_resettotal_disputes() {
         &initiator,
         &reason,
         &escrow_contract_addr,
+        &0,
         &dispute_amount,
     );
     let count_after_first = client.get_total_disputes();
@@ -506,6 +593,7 @@ fn test_resettotal_disputes() {
         &initiator,
         &reason,
         &escrow_contract_addr,
+        &0,
         &dispute_amount,
     );
     let count_after_second = client.get_total_disputes();
@@ -543,6 +631,7 @@ fn test_reset_total_disputes_fail() {
         &initiator,
         &reason,
         &escrow_contract_addr,
+        &0,
         &dispute_amount,
     );
     let count_after_first = client.get_total_disputes();
@@ -554,6 +643,7 @@ fn test_reset_total_disputes_fail() {
         &initiator,
         &reason,
         &escrow_contract_addr,
+        &0,
         &dispute_amount,
     );
     let count_after_second = client.get_total_disputes();
@@ -590,6 +680,7 @@ fn test_get_dispute_info() {
         &initiator,
         &reason,
         &escrow_contract,
+        &0,
         &dispute_amount,
     );
     client.assign_mediator(&job_id, &admin, &mediator);
@@ -629,6 +720,7 @@ fn test_dispute_timeout() {
         &initiator,
         &reason,
         &escrow_contract,
+        &0,
         &dispute_amount,
     );
 
@@ -70,6 +70,8 @@ pub struct DisputeData {
     pub dispute_amount: i128,
     pub fee_collected: i128,
     pub escrow_contract: Option<Address>, // Direct escrow integration
+    pub escrow_id: u32,                   // Disputed escrow in the escrow contract's registry
+    pub milestone_id: Option<u32>,        // Contested escrow milestone, None for the whole escrow
     pub timeout_timestamp: Option<u64>,   // Automatic resolution timeout
     pub evidence: Vec<Evidence>,
     pub mediator: Option<Address>,
@@ -147,6 +149,8 @@ pub struct DisputeInfo {
     pub dispute_amount: i128,
     pub fee_collected: i128,
     pub escrow_contract: Option<Address>, // Direct escrow integration
+    pub escrow_id: u32,                   // Disputed escrow in the escrow contract's registry
+    pub milestone_id: Option<u32>,        // Contested escrow milestone, None for the whole escrow
     pub timeout_timestamp: Option<u64>,   // Automatic resolution timeout
     pub evidence: Vec<(Address, String, u64, Option<String>)>,
     pub mediator: Option<Address>,
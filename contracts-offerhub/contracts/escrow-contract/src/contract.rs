@@ -1,18 +1,25 @@
-use soroban_sdk::{log, Address, Env, IntoVal, String, Symbol, Vec};
+use soroban_sdk::{log, token, xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, String, Symbol, Vec};
 
 use crate::storage::{
     check_rate_limit, increment_escrow_transaction_count, reset_rate_limit as rl_reset,
-    set_escrow_transaction_count, set_rate_limit_bypass_flag,
+    set_escrow_transaction_count, set_rate_limit_bypass_flag, transition_state,
+    load_escrow, save_escrow, next_escrow_id, load_cancellation_request,
+    set_cancellation_request, remove_cancellation_request, load_retainer, save_retainer,
+    load_stream, save_stream, load_amendment, set_amendment, remove_amendment, load_versions,
+    save_versions, load_tips, save_tips, load_hash_lock, save_hash_lock,
+    load_approval_key, save_approval_key, load_holdback, save_holdback, DEFAULT_ESCROW_ID,
+    REFUND_WINDOW_SECS,
 };
 use crate::{
     error::handle_error,
 
-    storage::{ESCROW_DATA, INITIALIZED, add_call_log, CallLog, CONTRACT_CONFIG, 
+    storage::{ADMIN, INITIALIZED, get_admin, append_audit_entry, load_audit_entries, MAX_AUDIT_PAGE, CONTRACT_CONFIG,
               DEFAULT_MIN_ESCROW_AMOUNT, DEFAULT_MAX_ESCROW_AMOUNT, DEFAULT_TIMEOUT_DAYS,
               DEFAULT_MAX_MILESTONES, DEFAULT_FEE_PERCENTAGE, DEFAULT_RATE_LIMIT_CALLS,
-              DEFAULT_RATE_LIMIT_WINDOW_HOURS},
-    types::{DisputeResult, EscrowData, Milestone, MilestoneHistory, ContractConfig},
-    validation::{validate_init_contract, validate_init_contract_full, validate_add_milestone, validate_milestone_id, validate_address},
+              DEFAULT_RATE_LIMIT_WINDOW_HOURS, DEFAULT_REVIEW_PERIOD_SECS,
+              DEFAULT_MAX_REVISIONS, DEFAULT_HOLDBACK_BPS, DEFAULT_HOLDBACK_SECS, RATING_CONTRACT},
+    types::{AmendmentProposal, AmendmentTerms, ApprovalKey, AuditAction, AuditEntry, HashLockTerms, Holdback, MilestoneApproval, EscrowVersion, MilestoneTerms, CancellationRequest, DisputeResolution, DisputeResult, EscrowData, FeeCalculation, FeeConfig, Milestone, MilestoneAction, MilestoneHistory, ContractConfig, RetainerSchedule, RetainerTerms, StreamSchedule, StreamTerms, TeamMember, TeamShare, TipRecord},
+    validation::{validate_init_contract, validate_init_contract_full, validate_add_milestone, validate_milestone_id, validate_address, validate_deliverable_note, validate_team_shares, validate_retainer_terms, validate_amount, validate_timeout},
 
     types::{
         EscrowDataExport, EscrowState, EscrowSummary
@@ -20,8 +27,12 @@ use crate::{
     error::Error,
 };
 
-const TOKEN_TRANSFER: &str = "transfer";
-const TOKEN_BALANCE: &str = "balance";
+const FEE_CALCULATE_ESCROW: &str = "calculate_escrow_fee";
+const FEE_CALCULATE_DISPUTE: &str = "calculate_dispute_fee";
+const FEE_CALCULATE_TIP: &str = "calculate_tip_fee";
+const FEE_GET_CONFIG: &str = "get_fee_config";
+const BPS_DENOMINATOR: u32 = 10_000;
+const MAX_TOKEN_DECIMALS: u32 = 18;
 const MAX_AGE: u64 = 365 * 24 * 60 * 60; // 1 year in seconds 31_536_000
 
 pub fn initialize_contract(env: &Env, admin: Address) {
@@ -39,9 +50,14 @@ pub fn initialize_contract(env: &Env, admin: Address) {
         fee_percentage: DEFAULT_FEE_PERCENTAGE,
         rate_limit_calls: DEFAULT_RATE_LIMIT_CALLS,
         rate_limit_window_hours: DEFAULT_RATE_LIMIT_WINDOW_HOURS,
+        review_period_secs: DEFAULT_REVIEW_PERIOD_SECS,
+        max_revisions: DEFAULT_MAX_REVISIONS,
+        holdback_bps: DEFAULT_HOLDBACK_BPS,
+        holdback_secs: DEFAULT_HOLDBACK_SECS,
     };
     
     env.storage().instance().set(&CONTRACT_CONFIG, &contract_config);
+    env.storage().instance().set(&ADMIN, &admin);
     
     env.events().publish(
         (Symbol::new(env, "contract_initialized"), admin),
@@ -49,20 +65,57 @@ pub fn initialize_contract(env: &Env, admin: Address) {
     );
 }
 
-// Helper function to log function calls
-fn log_function_call(env: &Env, function_name: &str, caller: &Address, success: bool) {
-    let log = CallLog {
-        function_name: String::from_str(env, function_name),
-        caller: caller.clone(),
+// Contract-wide settings belong to the admin from `initialize_contract`, not to the
+// client of any one escrow hosted here.
+fn require_admin(env: &Env, caller: &Address) {
+    caller.require_auth();
+    match get_admin(env) {
+        Some(admin) if admin == *caller => {}
+        Some(_) => handle_error(env, Error::Unauthorized),
+        None => handle_error(env, Error::NotInitialized),
+    }
+}
+
+// Limits an admin set through `initialize_contract`/`set_config`. Escrows without a
+// stored config keep the stateless validation only.
+fn active_config(env: &Env) -> Option<ContractConfig> {
+    env.storage().instance().get(&CONTRACT_CONFIG)
+}
+
+fn enforce_amount_limits(env: &Env, amount: i128) {
+    if let Some(config) = active_config(env) {
+        if amount < config.min_escrow_amount {
+            handle_error(env, Error::AmountBelowMinimum);
+        }
+        if amount > config.max_escrow_amount {
+            handle_error(env, Error::AmountAboveMaximum);
+        }
+    }
+}
+
+// Appends to the escrow's audit trail. Called once a mutation has passed every
+// check and been saved, so the trail only holds changes that took effect.
+fn audit(
+    env: &Env,
+    escrow_data: &EscrowData,
+    action: AuditAction,
+    actor: &Address,
+    before: EscrowState,
+    amount: i128,
+) {
+    let entry = AuditEntry {
+        action,
+        actor: actor.clone(),
+        before,
+        after: escrow_data.state.clone(),
+        amount,
+        ledger: env.ledger().sequence(),
         timestamp: env.ledger().timestamp(),
-        success,
-        gas_used: 0, // Soroban doesn't provide gas_used method
-        transaction_hash: String::from_str(env, "unknown"), // Soroban doesn't provide transaction_hash method
     };
-
-    add_call_log(env, &log);
+    append_audit_entry(env, escrow_data.escrow_id, &entry);
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn init_contract_full(
     env: &Env,
     client: Address,
@@ -71,17 +124,138 @@ pub fn init_contract_full(
     token: Address,
     amount: i128,
     timeout_secs: u64,
+    fee_manager: Address,
 ) {
+    if env.storage().instance().has(&INITIALIZED) {
+        handle_error(env, Error::AlreadyInitialized);
+    }
+
+    let escrow_data = new_escrow(
+        env,
+        DEFAULT_ESCROW_ID,
+        client,
+        freelancer,
+        arbitrator,
+        token,
+        amount,
+        timeout_secs,
+        fee_manager,
+        amount,
+    );
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::Created, &escrow_data.client, EscrowState::Created, escrow_data.amount);
+    env.storage().instance().set(&INITIALIZED, &true);
+}
+
+/// Registry mode: adds another escrow to this contract and returns its id.
+#[allow(clippy::too_many_arguments)]
+pub fn create_escrow(
+    env: &Env,
+    client: Address,
+    freelancer: Address,
+    arbitrator: Address,
+    token: Address,
+    amount: i128,
+    timeout_secs: u64,
+    fee_manager: Address,
+) -> u32 {
     let caller = client.clone();
 
-    // Log function call start
-    log_function_call(env, "init_contract_full", &caller, true);
+    client.require_auth();
 
-    if env.storage().instance().has(&INITIALIZED) {
-        handle_error(env, Error::AlreadyInitialized);
+    let escrow_id = next_escrow_id(env);
+    let escrow_data = new_escrow(
+        env,
+        escrow_id,
+        client,
+        freelancer,
+        arbitrator,
+        token,
+        amount,
+        timeout_secs,
+        fee_manager,
+        amount,
+    );
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::Created, &escrow_data.client, EscrowState::Created, amount);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_created"), caller),
+        (escrow_id, escrow_data.freelancer, amount, env.ledger().timestamp()),
+    );
+    escrow_id
+}
+
+/// Registry mode: an escrow paid out to a team. Every payout to the freelancer side
+/// is split across the members by share weight; the first member is the lead and
+/// is recorded as the escrow's `freelancer`.
+#[allow(clippy::too_many_arguments)]
+pub fn create_team_escrow(
+    env: &Env,
+    client: Address,
+    shares: Vec<TeamShare>,
+    arbitrator: Address,
+    token: Address,
+    amount: i128,
+    timeout_secs: u64,
+    fee_manager: Address,
+) -> u32 {
+    let caller = client.clone();
+
+    client.require_auth();
+
+    if let Err(e) = validate_team_shares(&client, &arbitrator, &shares) {
+        handle_error(env, e);
+    }
+
+    let escrow_id = next_escrow_id(env);
+    let lead = shares.get(0).unwrap().member;
+    let mut escrow_data = new_escrow(
+        env,
+        escrow_id,
+        client,
+        lead,
+        arbitrator,
+        token,
+        amount,
+        timeout_secs,
+        fee_manager,
+        amount,
+    );
+    for share in shares.iter() {
+        escrow_data.team.push_back(TeamMember {
+            member: share.member,
+            share_bps: share.share_bps,
+            earned: 0,
+            paid: 0,
+        });
     }
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::Created, &escrow_data.client, EscrowState::Created, amount);
 
+    env.events().publish(
+        (Symbol::new(env, "team_escrow_created"), caller),
+        (escrow_id, escrow_data.team.len(), amount, env.ledger().timestamp()),
+    );
+    escrow_id
+}
 
+// Validates the terms shared by the single-escrow and registry entry points.
+// `committed` is the total the configured amount limits apply to; it only differs
+// from `amount` for retainers, whose `amount` is a single period.
+#[allow(clippy::too_many_arguments)]
+fn new_escrow(
+    env: &Env,
+    escrow_id: u32,
+    client: Address,
+    freelancer: Address,
+    arbitrator: Address,
+    token: Address,
+    amount: i128,
+    timeout_secs: u64,
+    fee_manager: Address,
+    committed: i128,
+) -> EscrowData {
     // Validate timeout_secs timestamp
     if let Err(e) = validate_timestamp(env, env.ledger().timestamp() + timeout_secs) {
         handle_error(env, e);
@@ -96,15 +270,21 @@ pub fn init_contract_full(
         &token,
         amount,
         timeout_secs,
+        &fee_manager,
     ) {
         handle_error(env, e);
     }
-    let escrow_data = EscrowData {
-        client: client.clone(),
+    check_token(env, &token);
+    enforce_amount_limits(env, committed);
+
+    EscrowData {
+        escrow_id,
+        client,
         freelancer,
         arbitrator: Some(arbitrator),
         token: Some(token),
         amount,
+        funded_amount: 0,
         state: EscrowState::Created,
         dispute_result: DisputeResult::None as u32,
         created_at: env.ledger().timestamp(),
@@ -116,41 +296,48 @@ pub fn init_contract_full(
         milestones: Vec::new(env),
         milestone_history: Vec::new(env),
         released_amount: 0,
-        fee_manager: client,
+        fee_manager,
         fee_collected: 0,
         net_amount: amount,
-    };
-    env.storage().instance().set(&ESCROW_DATA, &escrow_data);
-    env.storage().instance().set(&INITIALIZED, &true);
+        freelancer_share_bps: 0,
+        freelancer_award: 0,
+        client_award: 0,
+        team: Vec::new(env),
+    }
 }
 
 pub fn init_contract(
     env: &Env,
     client: Address,
     freelancer: Address,
+    token: Address,
     amount: i128,
     fee_manager: Address,
 ) {
     let caller = client.clone();
 
-    // Log function call start
-    log_function_call(env, "init_contract", &caller, true);
-
     if env.storage().instance().has(&INITIALIZED) {
         handle_error(env, Error::AlreadyInitialized);
     }
 
     // Input validation
-    if let Err(e) = validate_init_contract(env, &client, &freelancer, amount, &fee_manager) {
+    if let Err(e) = validate_init_contract(env, &client, &freelancer, &token, amount, &fee_manager) {
         handle_error(env, e);
     }
+    check_token(env, &token);
+    enforce_amount_limits(env, amount);
+
+    // Without an explicit timeout the configured default applies
+    let timeout_secs = active_config(env).map(|c| c.default_timeout_days as u64 * 24 * 60 * 60);
 
     let escrow_data = EscrowData {
+        escrow_id: DEFAULT_ESCROW_ID,
         client,
         freelancer :  freelancer.clone(),
         arbitrator: None,
-        token: None,
+        token: Some(token),
         amount,
+        funded_amount: 0,
         state: EscrowState::Created,
         dispute_result: DisputeResult::None as u32,
         created_at: env.ledger().timestamp(),
@@ -158,33 +345,77 @@ pub fn init_contract(
         released_at: None,
         disputed_at: None,
         resolved_at: None,
-        timeout_secs: None,
+        timeout_secs,
         milestones: Vec::new(env),
         milestone_history: Vec::new(env),
         released_amount: 0,
         fee_manager: fee_manager.clone(),
         fee_collected: 0,
         net_amount: amount,
+        freelancer_share_bps: 0,
+        freelancer_award: 0,
+        client_award: 0,
+        team: Vec::new(env),
     };
 
-    env.storage().instance().set(&ESCROW_DATA, &escrow_data);
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::Created, &escrow_data.client, EscrowState::Created, amount);
     env.storage().instance().set(&INITIALIZED, &true);
     env.events().publish((Symbol::new(env  , "initiated_contract") ,caller ), (freelancer , amount , fee_manager , env.ledger().timestamp()));
 }
 
-pub fn deposit_funds(env: &Env, client: Address) {
-    let caller = client.clone();
+pub fn deposit_funds(env: &Env, escrow_id: u32, client: Address) {
+    client.require_auth();
 
-    // Log function call start
-    log_function_call(env, "deposit_funds", &caller, true);
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
 
-    client.require_auth();
+    // Validate timeout hasn't expired. A retainer's term may run well past a year;
+    // its own schedule bounds it instead.
+    if let Some(timeout_secs) = escrow_data.timeout_secs {
+        if load_retainer(env, escrow_id).is_none() {
+            if let Err(e) = validate_timestamp(env, escrow_data.created_at + timeout_secs) {
+                handle_error(env, e);
+            }
+        }
+    }
 
-    if !env.storage().instance().has(&INITIALIZED) {
-        handle_error(env, Error::NotInitialized);
+    if escrow_data.client != client {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if escrow_data.state != EscrowState::Created {
+        handle_error(env, Error::InvalidStatus);
     }
 
-    let mut escrow_data: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
+    collect_deposit(env, &escrow_data, &client, escrow_data.amount);
+    escrow_data.funded_amount = escrow_data.amount;
+
+    transition_state(env, &mut escrow_data, EscrowState::Funded);
+
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::Deposited, &client, before, escrow_data.amount);
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (Symbol::new(env, "deposited_fund"), client.clone()),
+        (escrow_data.amount, env.ledger().timestamp()),
+    );
+}
+
+// Funds the escrow in tranches. Each deposit adds to `funded_amount`; the escrow
+// is PartiallyFunded until the deposits cover `amount`, and milestones only pay
+// out of what has been deposited so far.
+pub fn deposit(env: &Env, escrow_id: u32, client: Address, amount: i128) {
+    client.require_auth();
+    reject_scheduled(env, escrow_id);
+
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
 
     // Validate timeout hasn't expired
     if let Some(timeout_secs) = escrow_data.timeout_secs {
@@ -197,31 +428,29 @@ pub fn deposit_funds(env: &Env, client: Address) {
         handle_error(env, Error::Unauthorized);
     }
 
-    if escrow_data.state != EscrowState::Created {
+    if escrow_data.state != EscrowState::Created && !escrow_data.state.is_active() {
         handle_error(env, Error::InvalidStatus);
     }
 
-    if let (Some(token), amount) = (escrow_data.token.clone(), escrow_data.amount) {
-        let balance: i128 = env.invoke_contract::<i128>(
-            &token,
-            &Symbol::new(env, TOKEN_BALANCE),
-            (client.clone(),).into_val(env),
-        );
-        if balance < amount {
-            handle_error(env, Error::InsufficientFunds);
-        }
-
-        env.invoke_contract::<()>(
-            &token,
-            &Symbol::new(env, TOKEN_TRANSFER),
-            (client.clone(), env.current_contract_address(), amount).into_val(env),
-        );
+    if let Err(e) = validate_amount(amount) {
+        handle_error(env, e);
+    }
+    if amount > escrow_data.amount - escrow_data.funded_amount {
+        handle_error(env, Error::InvalidAmount);
     }
 
-    escrow_data.state = EscrowState::Funded;
-    escrow_data.funded_at = Some(env.ledger().timestamp());
+    collect_deposit(env, &escrow_data, &client, amount);
+    escrow_data.funded_amount += amount;
+
+    if escrow_data.state == EscrowState::Created {
+        transition_state(env, &mut escrow_data, EscrowState::PartiallyFunded);
+    }
+    if escrow_data.state == EscrowState::PartiallyFunded && escrow_data.funded_amount == escrow_data.amount {
+        transition_state(env, &mut escrow_data, EscrowState::Funded);
+    }
 
-    env.storage().instance().set(&ESCROW_DATA, &escrow_data);
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::Deposited, &client, before, amount);
     let total_escrow_transaction = increment_escrow_transaction_count(env);
 
     env.events().publish(
@@ -229,24 +458,45 @@ pub fn deposit_funds(env: &Env, client: Address) {
         total_escrow_transaction,
     );
     env.events().publish(
-        (Symbol::new(env, "deposited_fund"), client.clone()),
-        (escrow_data.amount, env.ledger().timestamp()),
+        (Symbol::new(env, "escrow_deposit"), client),
+        (escrow_id, amount, escrow_data.funded_amount, env.ledger().timestamp()),
     );
 }
 
-pub fn release_funds(env: &Env, freelancer: Address) {
-    let caller = freelancer.clone();
-
-    // Log function call start
-    log_function_call(env, "release_funds", &caller, true);
+// Any SEP-41 token works, the native XLM Stellar Asset Contract included. The
+// decimals probe rejects addresses that are not token contracts at all.
+fn check_token(env: &Env, token: &Address) {
+    match token::Client::new(env, token).try_decimals() {
+        Ok(Ok(decimals)) if decimals <= MAX_TOKEN_DECIMALS => {}
+        _ => handle_error(env, Error::InvalidToken),
+    }
+}
 
-    freelancer.require_auth();
+// Pulls `amount` of the escrow token from `from` into the contract. A client that
+// approved the escrow for at least `amount` is drawn down with `transfer_from`;
+// otherwise the client signs a plain transfer.
+fn collect_deposit(env: &Env, escrow_data: &EscrowData, from: &Address, amount: i128) {
+    if let Some(token) = escrow_data.token.clone() {
+        let token = token::Client::new(env, &token);
+        if token.balance(from) < amount {
+            handle_error(env, Error::InsufficientFunds);
+        }
 
-    if !env.storage().instance().has(&INITIALIZED) {
-        handle_error(env, Error::NotInitialized);
+        let contract_addr = env.current_contract_address();
+        if token.allowance(from, &contract_addr) >= amount {
+            token.transfer_from(&contract_addr, from, &contract_addr, &amount);
+        } else {
+            token.transfer(from, &contract_addr, &amount);
+        }
     }
+}
+
+pub fn release_funds(env: &Env, escrow_id: u32, freelancer: Address) {
+    freelancer.require_auth();
+    reject_scheduled(env, escrow_id);
 
-    let mut escrow_data: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
 
     // Validate timeout hasn't expired
     if let Some(timeout_secs) = escrow_data.timeout_secs {
@@ -255,32 +505,34 @@ pub fn release_funds(env: &Env, freelancer: Address) {
         }
     }
 
-    if escrow_data.freelancer != freelancer {
+    if !is_payee(&escrow_data, &freelancer) {
         handle_error(env, Error::Unauthorized);
     }
 
-    if escrow_data.state != EscrowState::Funded {
+    if !escrow_data.state.is_active() {
         handle_error(env, Error::InvalidStatus);
     }
 
-    if let (Some(token), amount) = (escrow_data.token.clone(), escrow_data.amount) {
-        env.invoke_contract::<()>(
-            &token,
-            &Symbol::new(env, TOKEN_TRANSFER),
-            (env.current_contract_address(), freelancer.clone(), amount).into_val(env),
-        );
+    // Closing out needs every tranche in; a short-funded escrow pays per milestone
+    if escrow_data.funded_amount < escrow_data.amount {
+        handle_error(env, Error::InsufficientFunds);
     }
 
-    let fee_percentage = 250;
-    let fee_amount = (escrow_data.amount * fee_percentage) / 10000;
-    let net_amount = escrow_data.amount - fee_amount;
+    // Funds of a contested milestone stay put until the arbitrator rules on them
+    if has_disputed_milestone(&escrow_data) {
+        handle_error(env, Error::MilestoneDisputed);
+    }
+
+    // Milestones already paid out are deducted; only the remaining balance moves.
+    let remaining = remaining_balance(&escrow_data);
+    pay_final_release(env, &mut escrow_data, remaining);
 
-    escrow_data.state = EscrowState::Released;
-    escrow_data.released_at = Some(env.ledger().timestamp());
-    escrow_data.fee_collected = fee_amount;
-    escrow_data.net_amount = net_amount;
+    escrow_data.released_amount += remaining;
+    transition_state(env, &mut escrow_data, EscrowState::Released);
 
-    env.storage().instance().set(&ESCROW_DATA, &escrow_data);
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::Released, &freelancer, before, remaining);
+    register_completion(env, &escrow_data);
 
     let total_escrow_transaction = increment_escrow_transaction_count(env);
 
@@ -300,32 +552,29 @@ pub fn release_funds(env: &Env, freelancer: Address) {
     );
 }
 
-pub fn dispute(env: &Env, caller: Address) {
-    let caller_addr = caller.clone();
-
-    // Log function call start
-    log_function_call(env, "dispute", &caller_addr, true);
-
+pub fn dispute(env: &Env, escrow_id: u32, caller: Address) {
     caller.require_auth();
 
-    if !env.storage().instance().has(&INITIALIZED) {
-        handle_error(env, Error::NotInitialized);
-    }
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
 
-    let mut escrow_data: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
-
-    if escrow_data.client != caller && escrow_data.freelancer != caller {
+    if escrow_data.client != caller && !is_payee(&escrow_data, &caller) {
         handle_error(env, Error::Unauthorized);
     }
 
-    if escrow_data.state != EscrowState::Funded {
+    if !escrow_data.state.is_active() {
         handle_error(env, Error::InvalidStatus);
     }
 
-    escrow_data.state = EscrowState::Disputed;
-    escrow_data.disputed_at = Some(env.ledger().timestamp());
+    // Hash-locked escrows have no arbitrator to settle a dispute
+    if load_hash_lock(env, escrow_id).is_some() {
+        handle_error(env, Error::InvalidStatus);
+    }
 
-    env.storage().instance().set(&ESCROW_DATA, &escrow_data);
+    transition_state(env, &mut escrow_data, EscrowState::Disputed);
+
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::Disputed, &caller, before, 0);
 
     let total_escrow_transaction = increment_escrow_transaction_count(env);
 
@@ -339,19 +588,48 @@ pub fn dispute(env: &Env, caller: Address) {
     );
 }
 
-pub fn resolve_dispute(env: &Env, caller: Address, result: Symbol) {
-    let caller_addr = caller.clone();
-
-    // Log function call start
-    log_function_call(env, "resolve_dispute", &caller_addr, true);
+pub fn resolve_dispute(env: &Env, escrow_id: u32, caller: Address, result: Symbol) {
+    let freelancer_share_bps = freelancer_share_from_result(env, &result);
 
-    caller.require_auth();
+    let resolution = DisputeResolution {
+        freelancer_share_bps,
+        awarded_milestones: Vec::new(env),
+    };
+    settle_dispute(env, escrow_id, caller, resolution);
+}
 
-    if !env.storage().instance().has(&INITIALIZED) {
-        handle_error(env, Error::NotInitialized);
+// Maps the dispute-contract outcome symbols to the freelancer's share in basis points
+fn freelancer_share_from_result(env: &Env, result: &Symbol) -> u32 {
+    // CORREGIDO: Aceptar más variaciones de símbolos
+    if *result == Symbol::new(env, "client_wins") || *result == Symbol::new(env, "client") {
+        0
+    } else if *result == Symbol::new(env, "freelancer_wins")
+        || *result == Symbol::new(env, "freelancer")
+    {
+        BPS_DENOMINATOR
+    } else if *result == Symbol::new(env, "split") {
+        BPS_DENOMINATOR / 2
+    } else {
+        handle_error(env, Error::InvalidDisputeResult);
     }
+}
+
+pub fn resolve_dispute_with_split(
+    env: &Env,
+    escrow_id: u32,
+    caller: Address,
+    resolution: DisputeResolution,
+) {
+    settle_dispute(env, escrow_id, caller, resolution);
+}
+
+// Awarded milestones go to the freelancer in full; whatever else the escrow still
+// holds is split by `freelancer_share_bps`. Both sides pay the dispute fee.
+fn settle_dispute(env: &Env, escrow_id: u32, caller: Address, resolution: DisputeResolution) {
+    caller.require_auth();
 
-    let mut escrow_data: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
 
     if escrow_data.state != EscrowState::Disputed {
         handle_error(env, Error::DisputeNotOpen);
@@ -361,71 +639,65 @@ pub fn resolve_dispute(env: &Env, caller: Address, result: Symbol) {
         handle_error(env, Error::Unauthorized);
     }
 
-    // CORREGIDO: Aceptar más variaciones de símbolos
-    let dispute_result =
-        if result == Symbol::new(env, "client_wins") || result == Symbol::new(env, "client") {
-            DisputeResult::ClientWins
-        } else if result == Symbol::new(env, "freelancer_wins")
-            || result == Symbol::new(env, "freelancer")
-        {
-            DisputeResult::FreelancerWins
-        } else if result == Symbol::new(env, "split") {
-            DisputeResult::Split
-        } else {
-            handle_error(env, Error::InvalidDisputeResult);
-        };
+    if resolution.freelancer_share_bps > BPS_DENOMINATOR {
+        handle_error(env, Error::InvalidSplit);
+    }
 
-    if let (Some(token), amount) = (escrow_data.token.clone(), escrow_data.amount) {
-        let contract_addr = env.current_contract_address();
-        match dispute_result {
-            DisputeResult::ClientWins => {
-                env.invoke_contract::<()>(
-                    &token,
-                    &Symbol::new(env, TOKEN_TRANSFER),
-                    (contract_addr.clone(), escrow_data.client.clone(), amount).into_val(env),
-                );
-            }
-            DisputeResult::FreelancerWins => {
-                env.invoke_contract::<()>(
-                    &token,
-                    &Symbol::new(env, TOKEN_TRANSFER),
-                    (
-                        contract_addr.clone(),
-                        escrow_data.freelancer.clone(),
-                        amount,
-                    )
-                        .into_val(env),
-                );
-            }
-            DisputeResult::Split => {
-                let half = amount / 2;
-                env.invoke_contract::<()>(
-                    &token,
-                    &Symbol::new(env, TOKEN_TRANSFER),
-                    (contract_addr.clone(), escrow_data.client.clone(), half).into_val(env),
-                );
-                env.invoke_contract::<()>(
-                    &token,
-                    &Symbol::new(env, TOKEN_TRANSFER),
-                    (
-                        contract_addr.clone(),
-                        escrow_data.freelancer.clone(),
-                        amount - half,
-                    )
-                        .into_val(env),
-                );
-            }
-            DisputeResult::None => {
-                handle_error(env, Error::InvalidDisputeResult);
-            }
+    let ts = env.ledger().timestamp();
+    let mut milestone_award: i128 = 0;
+    for milestone_id in resolution.awarded_milestones.iter() {
+        if let Err(e) = validate_milestone_id(milestone_id) {
+            handle_error(env, e);
+        }
+        let index = milestone_id - 1;
+        if index >= escrow_data.milestones.len() {
+            handle_error(env, Error::MilestoneNotFound);
         }
+        let mut milestone = escrow_data.milestones.get(index).unwrap();
+        if milestone.released {
+            handle_error(env, Error::InvalidStatus);
+        }
+
+        milestone.released = true;
+        milestone.released_at = Some(ts);
+        milestone_award += milestone.amount;
+        escrow_data.milestones.set(index, milestone.clone());
+        escrow_data.milestone_history.push_back(MilestoneHistory {
+            milestone,
+            action: MilestoneAction::Awarded,
+            timestamp: ts,
+            reason_hash: None,
+        });
     }
 
-    escrow_data.state = EscrowState::Released; // CORREGIDO: cambiar a Released en lugar de Resolved
+    require_funded(env, &escrow_data, milestone_award);
+    let disputed = remaining_balance(&escrow_data) - milestone_award;
+    let freelancer_split = disputed * resolution.freelancer_share_bps as i128 / BPS_DENOMINATOR as i128;
+    let freelancer_amount = milestone_award + freelancer_split;
+    let client_amount = disputed - freelancer_split;
+
+    let client = escrow_data.client.clone();
+    pay_freelancers(env, &mut escrow_data, freelancer_amount, FEE_CALCULATE_DISPUTE);
+    pay_out(env, &mut escrow_data, &client, client_amount, FEE_CALCULATE_DISPUTE);
+    escrow_data.released_amount += milestone_award;
+
+    let dispute_result = if resolution.freelancer_share_bps == 0 && milestone_award == 0 {
+        DisputeResult::ClientWins
+    } else if client_amount == 0 {
+        DisputeResult::FreelancerWins
+    } else {
+        DisputeResult::Split
+    };
+
+    transition_state(env, &mut escrow_data, EscrowState::Resolved);
     escrow_data.dispute_result = dispute_result as u32;
-    escrow_data.resolved_at = Some(env.ledger().timestamp());
+    escrow_data.freelancer_share_bps = resolution.freelancer_share_bps;
+    escrow_data.freelancer_award = freelancer_amount;
+    escrow_data.client_award = client_amount;
 
-    env.storage().instance().set(&ESCROW_DATA, &escrow_data);
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::DisputeResolved, &caller, before, freelancer_amount + client_amount);
+    register_completion(env, &escrow_data);
 
     let total_escrow_transaction = increment_escrow_transaction_count(env);
 
@@ -434,29 +706,49 @@ pub fn resolve_dispute(env: &Env, caller: Address, result: Symbol) {
         total_escrow_transaction,
     );
     env.events().publish(
-        (Symbol::new(env, "escrow_resolved"), result.clone()),
-        env.ledger().timestamp(),
+        (Symbol::new(env, "escrow_resolved"), dispute_result),
+        (
+            resolution.freelancer_share_bps,
+            freelancer_amount,
+            client_amount,
+            resolution.awarded_milestones,
+            ts,
+        ),
     );
 }
 
-pub fn add_milestone(env: &Env, client: Address, desc: String, amount: i128) -> u32 {
-    let caller = client.clone();
-
-    // Log function call start
-    log_function_call(env, "add_milestone", &caller, true);
-
+pub fn add_milestone(
+    env: &Env,
+    escrow_id: u32,
+    client: Address,
+    desc: String,
+    amount: i128,
+    deadline: Option<u64>,
+) -> u32 {
     client.require_auth();
+    reject_scheduled(env, escrow_id);
 
     // Input validation
     if let Err(e) = validate_add_milestone(env, &client, &desc, amount) {
         handle_error(env, e);
     }
+    if let Some(deadline) = deadline {
+        if deadline <= env.ledger().timestamp() {
+            handle_error(env, Error::InvalidTimestamp);
+        }
+    }
 
-    let mut escrow: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
+    let mut escrow = load_escrow(env, escrow_id);
+    let before = escrow.state.clone();
 
-    // Rate limit: max 10 milestones per hour per client for this escrow
+    // Rate limit: milestones per window per client, from the live config
+    let config = active_config(env);
+    let (max_calls, window_hours) = config
+        .as_ref()
+        .map(|c| (c.rate_limit_calls, c.rate_limit_window_hours))
+        .unwrap_or((DEFAULT_RATE_LIMIT_CALLS, DEFAULT_RATE_LIMIT_WINDOW_HOURS));
     let limit_type = String::from_str(env, "add_milestone");
-    if let Err(e) = check_rate_limit(env, &client, &limit_type, 10, 3600) {
+    if let Err(e) = check_rate_limit(env, &client, &limit_type, max_calls, window_hours as u64 * 3600) {
         handle_error(env, e);
     }
 
@@ -464,6 +756,21 @@ pub fn add_milestone(env: &Env, client: Address, desc: String, amount: i128) ->
         handle_error(env, Error::Unauthorized);
     }
 
+    if let Some(config) = config {
+        if escrow.milestones.len() >= config.max_milestones {
+            handle_error(env, Error::TooManyMilestones);
+        }
+    }
+
+    // The milestones together can never promise more than the escrow holds
+    let mut committed: i128 = 0;
+    for m in escrow.milestones.iter() {
+        committed += m.amount;
+    }
+    if committed + amount > escrow.amount {
+        handle_error(env, Error::MilestoneAmountExceedsEscrow);
+    }
+
     let m_id = (escrow.milestones.len() as u32) + 1;
     let ts = env.ledger().timestamp();
 
@@ -476,16 +783,25 @@ pub fn add_milestone(env: &Env, client: Address, desc: String, amount: i128) ->
         created_at: ts,
         approved_at: None,
         released_at: None,
+        deliverable_hash: None,
+        deliverable_note: None,
+        submitted_at: None,
+        revision_count: 0,
+        deadline,
+        late: false,
+        disputed: false,
     };
 
     escrow.milestones.push_back(milestone.clone());
     escrow.milestone_history.push_back(MilestoneHistory {
         milestone: milestone,
-        action: String::from_str(env, "added"),
+        action: MilestoneAction::Added,
         timestamp: ts,
+        reason_hash: None,
     });
 
-    env.storage().instance().set(&ESCROW_DATA, &escrow);
+    save_escrow(env, &escrow);
+    audit(env, &escrow, AuditAction::MilestoneAdded, &client, before, amount);
 
     let total_escrow_transaction = increment_escrow_transaction_count(env);
 
@@ -495,41 +811,27 @@ pub fn add_milestone(env: &Env, client: Address, desc: String, amount: i128) ->
     );
     env.events().publish(
         (Symbol::new(env, "escrow_milestone_added"), client.clone()),
-        (m_id, desc.clone(), amount, env.ledger().timestamp()),
+        (m_id, desc.clone(), amount, deadline, env.ledger().timestamp()),
     );
     m_id
 }
 
-// Admin helpers for rate limiting: admin is the escrow client
+// Admin helpers for rate limiting
 pub fn set_rate_limit_bypass(env: &Env, caller: Address, user: Address, bypass: bool) {
-    caller.require_auth();
-    // Only escrow client can toggle bypass
-    let escrow: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
-    if escrow.client != caller {
-        handle_error(env, Error::Unauthorized);
-    }
+    require_admin(env, &caller);
     set_rate_limit_bypass_flag(env, &user, bypass);
 
     env.events().publish((Symbol::new(env , "set_rate_limit_bypass") , caller), (user , bypass , env.ledger().timestamp()));
 }
 
 pub fn reset_rate_limit(env: &Env, caller: Address, user: Address, limit_type: String) {
-    caller.require_auth();
-    let escrow: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
-    if escrow.client != caller {
-        handle_error(env, Error::Unauthorized);
-    }
+    require_admin(env, &caller);
     rl_reset(env, &user, &limit_type);
      env.events().publish((Symbol::new(env , "reset_rate_limit") , caller), (user , limit_type, env.ledger().timestamp()));
 }
 
 // CORREGIDO: usar índice correcto (milestone_id - 1)
-pub fn approve_milestone(env: &Env, client: Address, milestone_id: u32) {
-    let caller = client.clone();
-
-    // Log function call start
-    log_function_call(env, "approve_milestone", &caller, true);
-
+pub fn approve_milestone(env: &Env, escrow_id: u32, client: Address, milestone_id: u32) {
     client.require_auth();
 
     // Input validation
@@ -540,13 +842,20 @@ pub fn approve_milestone(env: &Env, client: Address, milestone_id: u32) {
         handle_error(env, e);
     }
 
-    let mut escrow: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
-    let ts = env.ledger().timestamp();
+    let escrow = load_escrow(env, escrow_id);
 
     if escrow.client != client {
         handle_error(env, Error::Unauthorized);
     }
 
+    mark_milestone_approved(env, escrow, milestone_id);
+}
+
+// Shared by the direct and the signed approval paths once the client is verified
+fn mark_milestone_approved(env: &Env, mut escrow: EscrowData, milestone_id: u32) {
+    let ts = env.ledger().timestamp();
+    let before = escrow.state.clone();
+
     // CORREGIDO: convertir milestone_id (base-1) a índice (base-0)
     let index = milestone_id - 1;
 
@@ -559,6 +868,14 @@ pub fn approve_milestone(env: &Env, client: Address, milestone_id: u32) {
     if milestone.approved {
         handle_error(env, Error::InvalidStatus);
     }
+    if milestone.disputed {
+        handle_error(env, Error::MilestoneDisputed);
+    }
+
+    // Without an on-chain deliverable, sign-off time stands in for delivery time
+    if milestone.submitted_at.is_none() && is_late(&milestone, ts) {
+        flag_late(env, &escrow, &mut milestone);
+    }
 
     milestone.approved = true;
     milestone.approved_at = Some(ts);
@@ -568,10 +885,12 @@ pub fn approve_milestone(env: &Env, client: Address, milestone_id: u32) {
 
     escrow.milestone_history.push_back(MilestoneHistory {
         milestone: milestone.clone(),
-        action: String::from_str(env, "approved"),
+        action: MilestoneAction::Approved,
         timestamp: ts,
+        reason_hash: None,
     });
-    env.storage().instance().set(&ESCROW_DATA, &escrow);
+    save_escrow(env, &escrow);
+    audit(env, &escrow, AuditAction::MilestoneApproved, &escrow.client, before, milestone.amount);
 
     let total_escrow_transaction = increment_escrow_transaction_count(env);
 
@@ -582,62 +901,1962 @@ pub fn approve_milestone(env: &Env, client: Address, milestone_id: u32) {
     env.events().publish(
         (
             Symbol::new(env, "escrow_milestone_approved"),
-            client.clone(),
+            escrow.client.clone(),
         ),
         (milestone_id, env.ledger().timestamp()),
     );
 }
 
-// CORREGIDO: usar índice correcto (milestone_id - 1)
-pub fn release_milestone(env: &Env, freelancer: Address, milestone_id: u32) {
-    let caller = freelancer.clone();
+// The client registers (or rotates) the ed25519 key its off-chain approvals are
+// signed with. Rotating keeps the nonce sequence, so old approvals stay spent.
+pub fn register_approval_key(env: &Env, client: Address, public_key: BytesN<32>) {
+    client.require_auth();
 
-    // Log function call start
-    log_function_call(env, "release_milestone", &caller, true);
+    let next_nonce = load_approval_key(env, &client).map(|k| k.next_nonce).unwrap_or(0);
+    save_approval_key(
+        env,
+        &client,
+        &ApprovalKey {
+            public_key: public_key.clone(),
+            next_nonce,
+        },
+    );
 
-    freelancer.require_auth();
+    env.events().publish(
+        (Symbol::new(env, "approval_key_registered"), client),
+        (public_key, env.ledger().timestamp()),
+    );
+}
 
-    // Input validation
-    if let Err(_) = validate_address(&freelancer) {
+pub fn get_approval_key(env: &Env, client: Address) -> ApprovalKey {
+    load_approval_key(env, &client).unwrap_or_else(|| handle_error(env, Error::ApprovalKeyNotFound))
+}
+
+// Approves a milestone on the strength of a message the client signed off-chain,
+// so anyone (typically a relayer) can submit it. Each approval carries the
+// client's next nonce and is consumed on use.
+pub fn approve_milestone_signed(
+    env: &Env,
+    escrow_id: u32,
+    approval: MilestoneApproval,
+    signature: BytesN<64>,
+) {
+    let escrow = load_escrow(env, escrow_id);
+
+    if approval.escrow != env.current_contract_address() || approval.escrow_id != escrow_id {
         handle_error(env, Error::Unauthorized);
     }
-    if let Err(e) = validate_milestone_id(milestone_id) {
+    if let Err(e) = validate_milestone_id(approval.milestone_id) {
         handle_error(env, e);
     }
+    if env.ledger().timestamp() > approval.expiry {
+        handle_error(env, Error::ApprovalExpired);
+    }
 
-    let mut escrow: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
-    let ts = env.ledger().timestamp();
-
-    if escrow.freelancer != freelancer {
-        handle_error(env, Error::Unauthorized);
+    let mut approval_key = get_approval_key(env, escrow.client.clone());
+    if approval.nonce != approval_key.next_nonce {
+        handle_error(env, Error::InvalidNonce);
     }
 
-    // CORREGIDO: convertir milestone_id (base-1) a índice (base-0)
-    let index = milestone_id - 1;
+    // Traps if the signature does not match the registered key
+    env.crypto().ed25519_verify(
+        &approval_key.public_key,
+        &approval.clone().to_xdr(env),
+        &signature,
+    );
 
-    if index >= escrow.milestones.len() {
+    approval_key.next_nonce += 1;
+    save_approval_key(env, &escrow.client, &approval_key);
+
+    mark_milestone_approved(env, escrow, approval.milestone_id);
+}
+
+// CORREGIDO: usar índice correcto (milestone_id - 1)
+pub fn release_milestone(env: &Env, escrow_id: u32, freelancer: Address, milestone_id: u32) {
+    freelancer.require_auth();
+
+    // Input validation
+    if let Err(_) = validate_address(&freelancer) {
+        handle_error(env, Error::Unauthorized);
+    }
+    if let Err(e) = validate_milestone_id(milestone_id) {
+        handle_error(env, e);
+    }
+
+    let mut escrow = load_escrow(env, escrow_id);
+    let before = escrow.state.clone();
+    let ts = env.ledger().timestamp();
+
+    if !is_payee(&escrow, &freelancer) {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if !escrow.state.is_active() {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    // CORREGIDO: convertir milestone_id (base-1) a índice (base-0)
+    let index = milestone_id - 1;
+
+    if index >= escrow.milestones.len() {
+        handle_error(env, Error::MilestoneNotFound);
+    }
+
+    let mut milestone = escrow.milestones.get(index).unwrap();
+
+    if milestone.disputed {
+        handle_error(env, Error::MilestoneDisputed);
+    }
+
+    // A client who stayed silent through the whole review window approves by default
+    if !milestone.approved && review_period_elapsed(env, &milestone) {
+        milestone.approved = true;
+        milestone.approved_at = Some(ts);
+        escrow.milestone_history.push_back(MilestoneHistory {
+            milestone: milestone.clone(),
+            action: MilestoneAction::AutoApproved,
+            timestamp: ts,
+            reason_hash: None,
+        });
+    }
+
+    if !milestone.approved || milestone.released {
+        handle_error(env, Error::InvalidStatus);
+    }
+    require_funded(env, &escrow, milestone.amount);
+
+    pay_freelancers(env, &mut escrow, milestone.amount, FEE_CALCULATE_ESCROW);
+
+    milestone.released = true;
+    milestone.released_at = Some(ts);
+    escrow.released_amount += milestone.amount;
+
+    // CORREGIDO: actualizar el milestone en el vector
+    escrow.milestones.set(index, milestone.clone());
+
+    escrow.milestone_history.push_back(MilestoneHistory {
+        milestone: milestone.clone(),
+        action: MilestoneAction::Released,
+        timestamp: ts,
+        reason_hash: None,
+    });
+
+    // Once the milestones have paid out the whole escrow there is nothing left to release
+    if escrow.released_amount == escrow.amount {
+        transition_state(env, &mut escrow, EscrowState::Completed);
+    } else if matches!(escrow.state, EscrowState::Funded | EscrowState::PartiallyFunded) {
+        transition_state(env, &mut escrow, EscrowState::PartiallyReleased);
+    }
+    save_escrow(env, &escrow);
+    audit(env, &escrow, AuditAction::MilestoneReleased, &freelancer, before, milestone.amount);
+    if escrow.state == EscrowState::Completed {
+        register_completion(env, &escrow);
+    }
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (
+            Symbol::new(env, "escrow_milestone_released"),
+            freelancer.clone(),
+        ),
+        (milestone_id, milestone.amount, ts),
+    );
+}
+
+fn review_period_secs(env: &Env) -> u64 {
+    active_config(env)
+        .map(|c| c.review_period_secs)
+        .unwrap_or(DEFAULT_REVIEW_PERIOD_SECS)
+}
+
+fn review_period_elapsed(env: &Env, milestone: &Milestone) -> bool {
+    match milestone.submitted_at {
+        Some(submitted_at) => env.ledger().timestamp() >= submitted_at + review_period_secs(env),
+        None => false,
+    }
+}
+
+// Freelancer hands in work for a milestone, which opens the client's review window.
+pub fn submit_deliverable(
+    env: &Env,
+    escrow_id: u32,
+    freelancer: Address,
+    milestone_id: u32,
+    content_hash: BytesN<32>,
+    note: String,
+) {
+    freelancer.require_auth();
+
+    // Input validation
+    if let Err(e) = validate_milestone_id(milestone_id) {
+        handle_error(env, e);
+    }
+    if let Err(e) = validate_deliverable_note(&note) {
+        handle_error(env, e);
+    }
+
+    let mut escrow = load_escrow(env, escrow_id);
+    let before = escrow.state.clone();
+    let ts = env.ledger().timestamp();
+
+    if !is_payee(&escrow, &freelancer) {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if !escrow.state.is_active() {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    let index = milestone_id - 1;
+    if index >= escrow.milestones.len() {
+        handle_error(env, Error::MilestoneNotFound);
+    }
+
+    let mut milestone = escrow.milestones.get(index).unwrap();
+
+    // Work already under review or signed off cannot be swapped out
+    if milestone.approved || milestone.released || milestone.submitted_at.is_some() {
+        handle_error(env, Error::InvalidStatus);
+    }
+    if milestone.disputed {
+        handle_error(env, Error::MilestoneDisputed);
+    }
+    if is_late(&milestone, ts) {
+        flag_late(env, &escrow, &mut milestone);
+    }
+
+    milestone.deliverable_hash = Some(content_hash.clone());
+    milestone.deliverable_note = Some(note);
+    milestone.submitted_at = Some(ts);
+    escrow.milestones.set(index, milestone.clone());
+
+    escrow.milestone_history.push_back(MilestoneHistory {
+        milestone,
+        action: MilestoneAction::Submitted,
+        timestamp: ts,
+        reason_hash: None,
+    });
+    save_escrow(env, &escrow);
+    audit(env, &escrow, AuditAction::DeliverableSubmitted, &freelancer, before, 0);
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (Symbol::new(env, "deliverable_submitted"), freelancer),
+        (milestone_id, content_hash, ts + review_period_secs(env)),
+    );
+}
+
+fn max_revisions(env: &Env) -> u32 {
+    active_config(env)
+        .map(|c| c.max_revisions)
+        .unwrap_or(DEFAULT_MAX_REVISIONS)
+}
+
+// Client sends the submitted work back; the freelancer has to submit again. Once the
+// milestone has used up its revision rounds, a further request escalates the escrow
+// to a dispute instead.
+pub fn request_revision(
+    env: &Env,
+    escrow_id: u32,
+    client: Address,
+    milestone_id: u32,
+    reason_hash: BytesN<32>,
+) {
+    client.require_auth();
+
+    if let Err(e) = validate_milestone_id(milestone_id) {
+        handle_error(env, e);
+    }
+
+    let mut escrow = load_escrow(env, escrow_id);
+    let before = escrow.state.clone();
+    let ts = env.ledger().timestamp();
+
+    if escrow.client != client {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if !escrow.state.is_active() {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    let index = milestone_id - 1;
+    if index >= escrow.milestones.len() {
+        handle_error(env, Error::MilestoneNotFound);
+    }
+
+    let mut milestone = escrow.milestones.get(index).unwrap();
+
+    if milestone.approved || milestone.released {
+        handle_error(env, Error::InvalidStatus);
+    }
+    if milestone.disputed {
+        handle_error(env, Error::MilestoneDisputed);
+    }
+    if milestone.submitted_at.is_none() {
+        handle_error(env, Error::DeliverableNotSubmitted);
+    }
+    if review_period_elapsed(env, &milestone) {
+        handle_error(env, Error::ReviewPeriodExpired);
+    }
+
+    let escalate = milestone.revision_count >= max_revisions(env);
+    let action = if escalate {
+        MilestoneAction::Escalated
+    } else {
+        milestone.revision_count += 1;
+        milestone.submitted_at = None;
+        MilestoneAction::RevisionRequested
+    };
+    escrow.milestones.set(index, milestone.clone());
+
+    escrow.milestone_history.push_back(MilestoneHistory {
+        milestone: milestone.clone(),
+        action,
+        timestamp: ts,
+        reason_hash: Some(reason_hash.clone()),
+    });
+    if escalate {
+        transition_state(env, &mut escrow, EscrowState::Disputed);
+    }
+    save_escrow(env, &escrow);
+    let audit_action = if escalate {
+        AuditAction::MilestoneDisputed
+    } else {
+        AuditAction::RevisionRequested
+    };
+    audit(env, &escrow, audit_action, &client, before, 0);
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    if escalate {
+        env.events().publish(
+            (Symbol::new(env, "escrow_disputed"), client),
+            (milestone_id, reason_hash, ts),
+        );
+    } else {
+        env.events().publish(
+            (Symbol::new(env, "revision_requested"), client),
+            (milestone_id, milestone.revision_count, reason_hash, ts),
+        );
+    }
+}
+
+// Delivery counts as late when it lands after the milestone's due date
+fn is_late(milestone: &Milestone, delivered_at: u64) -> bool {
+    matches!(milestone.deadline, Some(deadline) if delivered_at > deadline)
+}
+
+fn flag_late(env: &Env, escrow: &EscrowData, milestone: &mut Milestone) {
+    if milestone.late {
+        return;
+    }
+    milestone.late = true;
+    env.events().publish(
+        (Symbol::new(env, "milestone_late"), escrow.freelancer.clone()),
+        (escrow.escrow_id, milestone.id, milestone.deadline, env.ledger().timestamp()),
+    );
+}
+
+fn has_disputed_milestone(escrow: &EscrowData) -> bool {
+    escrow.milestones.iter().any(|m| m.disputed)
+}
+
+// Either party contests a single milestone. Only that milestone's funds freeze; the
+// other milestones keep being approved and released as usual.
+pub fn dispute_milestone(env: &Env, escrow_id: u32, caller: Address, milestone_id: u32) {
+    caller.require_auth();
+
+    if let Err(e) = validate_milestone_id(milestone_id) {
+        handle_error(env, e);
+    }
+
+    let mut escrow = load_escrow(env, escrow_id);
+    let before = escrow.state.clone();
+    let ts = env.ledger().timestamp();
+
+    if escrow.client != caller && !is_payee(&escrow, &caller) {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if !escrow.state.is_active() {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    let index = milestone_id - 1;
+    if index >= escrow.milestones.len() {
+        handle_error(env, Error::MilestoneNotFound);
+    }
+
+    let mut milestone = escrow.milestones.get(index).unwrap();
+
+    if milestone.released {
+        handle_error(env, Error::InvalidStatus);
+    }
+    if milestone.disputed {
+        handle_error(env, Error::MilestoneDisputed);
+    }
+
+    milestone.disputed = true;
+    escrow.milestones.set(index, milestone.clone());
+
+    escrow.milestone_history.push_back(MilestoneHistory {
+        milestone: milestone.clone(),
+        action: MilestoneAction::Disputed,
+        timestamp: ts,
+        reason_hash: None,
+    });
+    save_escrow(env, &escrow);
+    audit(env, &escrow, AuditAction::MilestoneDisputed, &caller, before, milestone.amount);
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (Symbol::new(env, "milestone_disputed"), caller),
+        (escrow.escrow_id, milestone_id, milestone.amount, ts),
+    );
+}
+
+// The arbitrator rules on the contested milestone only; its amount is split by
+// `result` and both sides pay the dispute fee, exactly like a full-escrow ruling.
+pub fn resolve_milestone_dispute(
+    env: &Env,
+    escrow_id: u32,
+    caller: Address,
+    milestone_id: u32,
+    result: Symbol,
+) {
+    caller.require_auth();
+
+    if let Err(e) = validate_milestone_id(milestone_id) {
+        handle_error(env, e);
+    }
+
+    let mut escrow = load_escrow(env, escrow_id);
+    let before = escrow.state.clone();
+    let ts = env.ledger().timestamp();
+
+    if escrow.arbitrator != Some(caller.clone()) {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if !escrow.state.is_active() {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    let index = milestone_id - 1;
+    if index >= escrow.milestones.len() {
         handle_error(env, Error::MilestoneNotFound);
     }
 
-    let mut milestone = escrow.milestones.get(index).unwrap();
+    let mut milestone = escrow.milestones.get(index).unwrap();
+
+    if !milestone.disputed {
+        handle_error(env, Error::DisputeNotOpen);
+    }
+
+    require_funded(env, &escrow, milestone.amount);
+
+    let freelancer_share_bps = freelancer_share_from_result(env, &result);
+    let freelancer_amount = milestone.amount * freelancer_share_bps as i128 / BPS_DENOMINATOR as i128;
+    let client_amount = milestone.amount - freelancer_amount;
+
+    let client = escrow.client.clone();
+    pay_freelancers(env, &mut escrow, freelancer_amount, FEE_CALCULATE_DISPUTE);
+    pay_out(env, &mut escrow, &client, client_amount, FEE_CALCULATE_DISPUTE);
+
+    milestone.disputed = false;
+    milestone.released = true;
+    milestone.released_at = Some(ts);
+    escrow.released_amount += milestone.amount;
+    escrow.milestones.set(index, milestone.clone());
+
+    escrow.milestone_history.push_back(MilestoneHistory {
+        milestone,
+        action: MilestoneAction::DisputeResolved,
+        timestamp: ts,
+        reason_hash: None,
+    });
+
+    if escrow.released_amount == escrow.amount {
+        transition_state(env, &mut escrow, EscrowState::Completed);
+    } else if matches!(escrow.state, EscrowState::Funded | EscrowState::PartiallyFunded) {
+        transition_state(env, &mut escrow, EscrowState::PartiallyReleased);
+    }
+    save_escrow(env, &escrow);
+    audit(env, &escrow, AuditAction::MilestoneDisputeResolved, &caller, before, freelancer_amount + client_amount);
+    if escrow.state == EscrowState::Completed {
+        register_completion(env, &escrow);
+    }
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (Symbol::new(env, "milestone_dispute_resolved"), caller),
+        (escrow.escrow_id, milestone_id, freelancer_amount, client_amount, ts),
+    );
+}
+
+pub fn get_escrow_data(env: &Env, escrow_id: u32) -> EscrowData {
+    load_escrow(env, escrow_id)
+}
+
+// Funds still held by the escrow: what has been deposited less what was paid out.
+fn remaining_balance(escrow_data: &EscrowData) -> i128 {
+    escrow_data.funded_amount - escrow_data.released_amount
+}
+
+// Milestone payouts must be covered by the deposits made so far
+fn require_funded(env: &Env, escrow_data: &EscrowData, amount: i128) {
+    if amount > remaining_balance(escrow_data) {
+        handle_error(env, Error::InsufficientFunds);
+    }
+}
+
+// The freelancer, or any member of a team escrow
+fn is_payee(escrow_data: &EscrowData, addr: &Address) -> bool {
+    escrow_data.freelancer == *addr || escrow_data.team.iter().any(|m| m.member == *addr)
+}
+
+// Pays the freelancer side of the escrow. Team escrows split `amount` by share
+// weight and book each member's earned/paid totals; the rounding dust goes to
+// the lead member.
+fn pay_freelancers(env: &Env, escrow_data: &mut EscrowData, amount: i128, fee_fn: &str) {
+    if escrow_data.team.is_empty() {
+        let freelancer = escrow_data.freelancer.clone();
+        pay_out(env, escrow_data, &freelancer, amount, fee_fn);
+        return;
+    }
+
+    let mut team = escrow_data.team.clone();
+    let mut lead_portion = amount;
+    for i in (0..team.len()).rev() {
+        let mut member = team.get(i).unwrap();
+        let portion = if i == 0 {
+            lead_portion
+        } else {
+            amount * member.share_bps as i128 / BPS_DENOMINATOR as i128
+        };
+        lead_portion -= portion;
+
+        let fee = pay_out(env, escrow_data, &member.member, portion, fee_fn);
+        member.earned += portion;
+        member.paid += portion - fee;
+        team.set(i, member);
+    }
+    escrow_data.team = team;
+}
+
+// Pays out the final release of the escrow. When the config sets a holdback, that
+// share stays in the contract for the client's grace window; see `release_holdback`.
+fn pay_final_release(env: &Env, escrow_data: &mut EscrowData, amount: i128) {
+    let (holdback_bps, holdback_secs) = active_config(env)
+        .map(|c| (c.holdback_bps, c.holdback_secs))
+        .unwrap_or((DEFAULT_HOLDBACK_BPS, DEFAULT_HOLDBACK_SECS));
+    let held = amount * holdback_bps as i128 / BPS_DENOMINATOR as i128;
+
+    pay_freelancers(env, escrow_data, amount - held, FEE_CALCULATE_ESCROW);
+    if held <= 0 {
+        return;
+    }
+
+    let holdback = Holdback {
+        amount: held,
+        expires_at: env.ledger().timestamp() + holdback_secs,
+        claim_amount: 0,
+        claim_hash: None,
+        refunded: 0,
+        settled: false,
+    };
+    save_holdback(env, escrow_data.escrow_id, &holdback);
+
+    env.events().publish(
+        (Symbol::new(env, "holdback_opened"), escrow_data.client.clone()),
+        (escrow_data.escrow_id, held, holdback.expires_at),
+    );
+}
+
+// Pays `amount` out of the escrow to `payee`. The fee quoted by the fee manager
+// (`fee_fn` selects the escrow or dispute rate) goes to the platform wallet and
+// only the net reaches `payee`. Escrows without a token move no funds.
+fn pay_out(env: &Env, escrow_data: &mut EscrowData, payee: &Address, amount: i128, fee_fn: &str) -> i128 {
+    let token = match escrow_data.token.clone() {
+        Some(token) => token::Client::new(env, &token),
+        None => return 0,
+    };
+    if amount <= 0 {
+        return 0;
+    }
+
+    let fee: FeeCalculation = env.invoke_contract(
+        &escrow_data.fee_manager,
+        &Symbol::new(env, fee_fn),
+        (amount, payee.clone()).into_val(env),
+    );
+    if fee.fee_amount < 0 || fee.fee_amount > amount {
+        handle_error(env, Error::InvalidAmount);
+    }
+    // Release fees may not exceed the configured platform fee; dispute fees are priced separately
+    if fee_fn == FEE_CALCULATE_ESCROW {
+        if let Some(config) = active_config(env) {
+            if fee.fee_amount * BPS_DENOMINATOR as i128 > amount * config.fee_percentage {
+                handle_error(env, Error::FeeExceedsLimit);
+            }
+        }
+    }
+
+    let contract_addr = env.current_contract_address();
+    if fee.fee_amount > 0 {
+        let fee_config: FeeConfig = env.invoke_contract(
+            &escrow_data.fee_manager,
+            &Symbol::new(env, FEE_GET_CONFIG),
+            Vec::new(env),
+        );
+        token.transfer(&contract_addr, &fee_config.platform_wallet, &fee.fee_amount);
+    }
+    token.transfer(&contract_addr, payee, &(amount - fee.fee_amount));
+
+    escrow_data.fee_collected += fee.fee_amount;
+    escrow_data.net_amount = escrow_data.amount - escrow_data.fee_collected;
+
+    env.events().publish(
+        (Symbol::new(env, "fee_withheld"), payee.clone()),
+        (amount, fee.fee_amount, fee.fee_percentage, fee.is_premium),
+    );
+
+    fee.fee_amount
+}
+
+pub fn auto_release(env: &Env, escrow_id: u32) {
+    reject_scheduled(env, escrow_id);
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+    if !escrow_data.state.is_active() {
+        handle_error(env, Error::InvalidStatus);
+    }
+    let funded_at = escrow_data.funded_at.unwrap_or(0);
+    let timeout = escrow_data.timeout_secs.unwrap_or(0);
+    let now = env.ledger().timestamp();
+    
+    // Validate timestamp
+    if let Err(e) = validate_timestamp(env, funded_at + timeout) {
+        handle_error(env, e);
+    }
+
+    // Undelivered work stays with the client through the refund window first
+    let mut release_at = funded_at + timeout;
+    if !work_delivered(&escrow_data) {
+        release_at += REFUND_WINDOW_SECS;
+    }
+
+    if now < release_at {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    if escrow_data.funded_amount < escrow_data.amount {
+        handle_error(env, Error::InsufficientFunds);
+    }
+
+    if has_disputed_milestone(&escrow_data) {
+        handle_error(env, Error::MilestoneDisputed);
+    }
+
+    let remaining = remaining_balance(&escrow_data);
+    pay_final_release(env, &mut escrow_data, remaining);
+
+    escrow_data.released_amount += remaining;
+    transition_state(env, &mut escrow_data, EscrowState::Released);
+
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::AutoReleased, &env.current_contract_address(), before, remaining);
+    register_completion(env, &escrow_data);
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (
+            Symbol::new(env, "auto_released"),
+            escrow_data.freelancer.clone(),
+        ),
+        (escrow_data.amount, escrow_data.net_amount, escrow_data.fee_collected, now),
+    );
+}
+
+// Returns whatever the escrow still holds to the client. Refunds carry no platform fee.
+fn refund_client_balance(env: &Env, escrow_data: &EscrowData) -> i128 {
+    let remaining = remaining_balance(escrow_data);
+    return_to_client(env, escrow_data, remaining);
+    remaining
+}
+
+fn return_to_client(env: &Env, escrow_data: &EscrowData, amount: i128) {
+    if let Some(token) = escrow_data.token.clone() {
+        if amount > 0 {
+            token::Client::new(env, &token).transfer(
+                &env.current_contract_address(),
+                &escrow_data.client,
+                &amount,
+            );
+        }
+    }
+}
+
+pub fn request_cancellation(env: &Env, escrow_id: u32, caller: Address) {
+    caller.require_auth();
+
+    let escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+
+    if escrow_data.client != caller && !is_payee(&escrow_data, &caller) {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if !escrow_data.state.is_active() {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    let request = CancellationRequest {
+        requested_by: caller.clone(),
+        requested_at: env.ledger().timestamp(),
+    };
+    set_cancellation_request(env, escrow_id, &request);
+    audit(env, &escrow_data, AuditAction::CancellationRequested, &caller, before, 0);
+
+    env.events().publish(
+        (Symbol::new(env, "cancellation_requested"), caller),
+        request.requested_at,
+    );
+}
+
+// The counterparty of the pending request signs off and the client is refunded.
+pub fn approve_cancellation(env: &Env, escrow_id: u32, caller: Address) {
+    caller.require_auth();
+
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+
+    if escrow_data.client != caller && !is_payee(&escrow_data, &caller) {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if !escrow_data.state.is_active() {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    let request: CancellationRequest = load_cancellation_request(env, escrow_id)
+        .unwrap_or_else(|| handle_error(env, Error::CancellationNotRequested));
+
+    // Only the other side may sign off: the client on a payee's request and vice versa
+    if (request.requested_by == escrow_data.client) == (escrow_data.client == caller) {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    let refunded = refund_client_balance(env, &escrow_data);
+    let now = env.ledger().timestamp();
+
+    transition_state(env, &mut escrow_data, EscrowState::Refunded);
+
+    save_escrow(env, &escrow_data);
+    remove_cancellation_request(env, escrow_id);
+    audit(env, &escrow_data, AuditAction::Cancelled, &caller, before, refunded);
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (Symbol::new(env, "escrow_cancelled"), escrow_data.client.clone()),
+        (request.requested_by, caller, refunded, now),
+    );
+}
+
+// Client reclaims the funds once the deadline has passed without any delivery.
+// Any submitted, approved or released milestone means the freelancer delivered something
+fn work_delivered(escrow_data: &EscrowData) -> bool {
+    escrow_data
+        .milestones
+        .iter()
+        .any(|m| m.approved || m.released || m.submitted_at.is_some())
+}
+
+pub fn refund_client(env: &Env, escrow_id: u32, client: Address) {
+    client.require_auth();
+    reject_scheduled(env, escrow_id);
+
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+
+    if escrow_data.client != client {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if !escrow_data.state.is_active() {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    let now = env.ledger().timestamp();
+    let deadline = escrow_data.funded_at.unwrap_or(0) + escrow_data.timeout_secs.unwrap_or(0);
+    if escrow_data.timeout_secs.is_none() || now < deadline {
+        handle_error(env, Error::DeadlineNotReached);
+    }
+
+    if work_delivered(&escrow_data) {
+        handle_error(env, Error::WorkAlreadyDelivered);
+    }
+
+    // After the window auto-release takes over, so the two never overlap
+    if now >= deadline + REFUND_WINDOW_SECS {
+        handle_error(env, Error::RefundWindowClosed);
+    }
+
+    let refunded = refund_client_balance(env, &escrow_data);
+
+    transition_state(env, &mut escrow_data, EscrowState::Refunded);
+
+    save_escrow(env, &escrow_data);
+    remove_cancellation_request(env, escrow_id);
+    audit(env, &escrow_data, AuditAction::Refunded, &client, before, refunded);
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (Symbol::new(env, "escrow_refunded"), client),
+        (refunded, now),
+    );
+}
+
+// Unfunded escrows can be called off by the client; no tokens are involved.
+pub fn cancel_escrow(env: &Env, escrow_id: u32, client: Address) {
+    client.require_auth();
+
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+
+    if escrow_data.client != client {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if escrow_data.state != EscrowState::Created {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    let now = env.ledger().timestamp();
+    transition_state(env, &mut escrow_data, EscrowState::Cancelled);
+
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::Cancelled, &client, before, 0);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_cancelled"), client),
+        (escrow_data.amount, now),
+    );
+}
+
+pub fn get_cancellation_request(env: &Env, escrow_id: u32) -> Option<CancellationRequest> {
+    load_cancellation_request(env, escrow_id)
+}
+
+pub fn get_milestones(env: &Env, escrow_id: u32) -> Vec<Milestone> {
+    let escrow = load_escrow(env, escrow_id);
+    escrow.milestones.clone()
+}
+
+pub fn get_milestone_history(env: &Env, escrow_id: u32) -> Vec<MilestoneHistory> {
+    let escrow = load_escrow(env, escrow_id);
+    escrow.milestone_history.clone()
+}
+
+pub fn set_escrow_data(env: &Env, data: &EscrowData) {
+    save_escrow(env, data);
+}
+
+// Logging functions
+// Page of the escrow's audit trail, oldest first; `limit` is capped at MAX_AUDIT_PAGE
+pub fn get_audit_log(env: &Env, escrow_id: u32, start: u32, limit: u32) -> Vec<AuditEntry> {
+    load_audit_entries(env, escrow_id, start, limit.min(MAX_AUDIT_PAGE))
+}
+
+pub fn get_audit_count(env: &Env, escrow_id: u32) -> u32 {
+    crate::storage::get_audit_count(env, escrow_id)
+}
+
+pub fn set_config(env: &Env, caller: Address, config: ContractConfig) {
+    require_admin(env, &caller);
+    
+    // Validate config parameters
+    if let Err(e) = validate_config(&config) {
+        handle_error(env, e);
+    }
+    
+    env.storage().instance().set(&CONTRACT_CONFIG, &config);
+    
+    env.events().publish(
+        (Symbol::new(env, "cfg_upd"), caller),
+        (config.min_escrow_amount, config.max_escrow_amount, config.default_timeout_days),
+    );
+}
+
+pub fn get_config(env: &Env) -> ContractConfig {
+    if !env.storage().instance().has(&CONTRACT_CONFIG) {
+        handle_error(env, Error::NotInitialized);
+    }
+    env.storage().instance().get(&CONTRACT_CONFIG).unwrap()
+}
+
+// Rating contract that finished escrows register their completion with. It is
+// invoked on every release of every escrow hosted here, so only the contract admin
+// may point it somewhere, and the event keeps the previous target on record.
+pub fn set_rating_contract(env: &Env, caller: Address, rating_contract: Address) {
+    require_admin(env, &caller);
+
+    let previous = get_rating_contract(env);
+    env.storage().instance().set(&RATING_CONTRACT, &rating_contract);
+
+    env.events().publish(
+        (Symbol::new(env, "rating_contract_set"), caller),
+        (previous, rating_contract),
+    );
+}
+
+pub fn get_rating_contract(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&RATING_CONTRACT)
+}
+
+// Registers a released or resolved escrow with the rating contract, which opens
+// the rating window for both parties. A missing or failing rating contract never
+// holds up the payout itself.
+fn register_completion(env: &Env, escrow_data: &EscrowData) {
+    let rating_contract = match get_rating_contract(env) {
+        Some(rating_contract) => rating_contract,
+        None => return,
+    };
+
+    let result = env.try_invoke_contract::<(), soroban_sdk::Error>(
+        &rating_contract,
+        &Symbol::new(env, "register_completion"),
+        (
+            env.current_contract_address(),
+            escrow_data.escrow_id,
+            escrow_data.client.clone(),
+            escrow_data.freelancer.clone(),
+            escrow_data.amount,
+            escrow_data.dispute_result,
+        )
+            .into_val(env),
+    );
+    if result.is_ok() {
+        env.events().publish(
+            (Symbol::new(env, "completion_registered"), rating_contract),
+            (escrow_data.escrow_id, escrow_data.dispute_result, env.ledger().timestamp()),
+        );
+    }
+}
+
+// Helper function to validate config parameters
+fn validate_config(config: &ContractConfig) -> Result<(), Error> {
+    // Validate escrow amounts
+    if config.min_escrow_amount >= config.max_escrow_amount {
+        return Err(Error::InvalidAmount);
+    }
+    
+    if config.min_escrow_amount < 1 {
+        return Err(Error::InvalidAmount);
+    }
+    
+    // Validate timeout (1-365 days)
+    if config.default_timeout_days < 1 || config.default_timeout_days > 365 {
+        return Err(Error::InvalidAmount);
+    }
+    
+    // Validate max milestones (1-100)
+    if config.max_milestones < 1 || config.max_milestones > 100 {
+        return Err(Error::InvalidAmount);
+    }
+    
+    // Validate fee percentage (0-10%)
+    if config.fee_percentage > 1000 {
+        return Err(Error::InvalidAmount);
+    }
+    
+    // Validate rate limit parameters
+    if config.rate_limit_window_hours < 1 || config.rate_limit_window_hours > 168 {
+        return Err(Error::InvalidAmount);
+    }
+    
+    if config.rate_limit_calls < 1 || config.rate_limit_calls > 1000 {
+        return Err(Error::InvalidAmount);
+    }
+
+    // Validate review period (1 hour - 30 days)
+    if config.review_period_secs < 3600 || config.review_period_secs > 30 * 24 * 60 * 60 {
+        return Err(Error::InvalidAmount);
+    }
+
+    // Validate revision rounds (1-20)
+    if config.max_revisions < 1 || config.max_revisions > 20 {
+        return Err(Error::InvalidAmount);
+    }
+
+    // Validate holdback (0-50%, grace window 1 hour - 90 days)
+    if config.holdback_bps > BPS_DENOMINATOR / 2 {
+        return Err(Error::InvalidAmount);
+    }
+    if config.holdback_secs < 3600 || config.holdback_secs > 90 * 24 * 60 * 60 {
+        return Err(Error::InvalidAmount);
+    }
+    
+    Ok(())
+}
+
+pub fn get_total_transactions(env: &Env) -> u64 {
+    crate::storage::get_total_transactions(env)
+}
+
+pub fn reset_transaction_count(env: &Env, admin: Address) -> Result<(), Error> {
+    require_admin(env, &admin);
+    set_escrow_transaction_count(&env, 0u64);
+
+    env.events().publish(
+        (
+            Symbol::new(env, "transaction_count_reset"),
+            admin,
+        ),
+        env.ledger().timestamp(),
+    );
+    Ok(())
+}
+
+// ==================== AMENDMENTS ====================
+
+// Either party proposes new terms; the other side has to accept them. A client
+// asking to raise the amount escrows the difference right away, so acceptance
+// by the freelancer alone can settle the amendment.
+pub fn propose_amendment(env: &Env, escrow_id: u32, caller: Address, terms: AmendmentTerms) {
+    caller.require_auth();
+    reject_scheduled(env, escrow_id);
+
+    let escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+
+    if escrow_data.client != caller && !is_payee(&escrow_data, &caller) {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if escrow_data.state != EscrowState::Created && !escrow_data.state.is_active() {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    if load_amendment(env, escrow_id).is_some() {
+        handle_error(env, Error::AmendmentPending);
+    }
+
+    validate_amendment(env, &escrow_data, &terms);
+
+    let mut deposited = 0;
+    if caller == escrow_data.client && escrow_data.state.is_active() && terms.amount > escrow_data.amount {
+        deposited = terms.amount - escrow_data.amount;
+        collect_deposit(env, &escrow_data, &caller, deposited);
+    }
+
+    let proposal = AmendmentProposal {
+        proposed_by: caller.clone(),
+        proposed_at: env.ledger().timestamp(),
+        terms,
+        deposited,
+    };
+    set_amendment(env, escrow_id, &proposal);
+    audit(env, &escrow_data, AuditAction::AmendmentProposed, &caller, before, deposited);
+
+    env.events().publish(
+        (Symbol::new(env, "amendment_proposed"), caller),
+        (escrow_id, proposal.terms.amount, proposal.terms.timeout_secs, deposited, proposal.proposed_at),
+    );
+}
+
+// Checks proposed terms against what has already happened on the escrow: paid
+// out funds and delivered milestones cannot be amended away.
+fn validate_amendment(env: &Env, escrow_data: &EscrowData, terms: &AmendmentTerms) {
+    if let Err(e) = validate_amount(terms.amount) {
+        handle_error(env, e);
+    }
+    enforce_amount_limits(env, terms.amount);
+    if terms.amount < escrow_data.released_amount {
+        handle_error(env, Error::InvalidAmount);
+    }
+    // Amendments settle against a fully funded balance
+    if escrow_data.state.is_active() && escrow_data.funded_amount < escrow_data.amount {
+        handle_error(env, Error::InvalidStatus);
+    }
+    if let Some(timeout_secs) = terms.timeout_secs {
+        if let Err(e) = validate_timeout(timeout_secs) {
+            handle_error(env, e);
+        }
+    }
+    if has_disputed_milestone(escrow_data) {
+        handle_error(env, Error::MilestoneDisputed);
+    }
+
+    let mut committed: i128 = 0;
+    if terms.replace_milestones {
+        if let Some(config) = active_config(env) {
+            if terms.milestones.len() > config.max_milestones {
+                handle_error(env, Error::TooManyMilestones);
+            }
+        }
+        let count = escrow_data.milestones.len().max(terms.milestones.len());
+        for i in 0..count {
+            let proposed = terms.milestones.get(i);
+            if let Some(existing) = escrow_data.milestones.get(i) {
+                if existing.approved || existing.released {
+                    match proposed {
+                        Some(ref p) if p.amount == existing.amount => {}
+                        _ => handle_error(env, Error::WorkAlreadyDelivered),
+                    }
+                }
+            }
+            if let Some(p) = proposed {
+                if let Err(e) = validate_add_milestone(env, &escrow_data.client, &p.description, p.amount) {
+                    handle_error(env, e);
+                }
+                if let Some(deadline) = p.deadline {
+                    if deadline <= env.ledger().timestamp() {
+                        handle_error(env, Error::InvalidTimestamp);
+                    }
+                }
+                committed += p.amount;
+            }
+        }
+    } else {
+        for m in escrow_data.milestones.iter() {
+            committed += m.amount;
+        }
+    }
+    if committed > terms.amount {
+        handle_error(env, Error::MilestoneAmountExceedsEscrow);
+    }
+}
+
+// Rewrites the milestone set position by position so milestone ids stay stable.
+// Approved, released and unchanged milestones are carried over as they are.
+fn apply_milestone_terms(env: &Env, escrow_data: &mut EscrowData, terms: &Vec<MilestoneTerms>) {
+    let ts = env.ledger().timestamp();
+    let mut milestones = Vec::new(env);
+
+    for (i, t) in terms.iter().enumerate() {
+        if let Some(existing) = escrow_data.milestones.get(i as u32) {
+            let unchanged = existing.description == t.description
+                && existing.amount == t.amount
+                && existing.deadline == t.deadline;
+            if existing.approved || existing.released || unchanged {
+                milestones.push_back(existing);
+                continue;
+            }
+        }
+
+        let milestone = Milestone {
+            id: i as u32 + 1,
+            description: t.description,
+            amount: t.amount,
+            approved: false,
+            released: false,
+            created_at: ts,
+            approved_at: None,
+            released_at: None,
+            deliverable_hash: None,
+            deliverable_note: None,
+            submitted_at: None,
+            revision_count: 0,
+            deadline: t.deadline,
+            late: false,
+            disputed: false,
+        };
+        escrow_data.milestone_history.push_back(MilestoneHistory {
+            milestone: milestone.clone(),
+            action: MilestoneAction::Amended,
+            timestamp: ts,
+            reason_hash: None,
+        });
+        milestones.push_back(milestone);
+    }
+
+    for i in terms.len()..escrow_data.milestones.len() {
+        escrow_data.milestone_history.push_back(MilestoneHistory {
+            milestone: escrow_data.milestones.get(i).unwrap(),
+            action: MilestoneAction::Removed,
+            timestamp: ts,
+            reason_hash: None,
+        });
+    }
+    escrow_data.milestones = milestones;
+}
+
+// The counterparty signs the pending amendment. A higher amount is topped up by
+// the client, a lower one is refunded to them; both settle in this call.
+pub fn accept_amendment(env: &Env, escrow_id: u32, caller: Address) {
+    caller.require_auth();
+
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+
+    if escrow_data.client != caller && !is_payee(&escrow_data, &caller) {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    let proposal = load_amendment(env, escrow_id)
+        .unwrap_or_else(|| handle_error(env, Error::AmendmentNotFound));
+
+    // Only the other side may sign: the client on a payee's proposal and vice versa
+    if (proposal.proposed_by == escrow_data.client) == (escrow_data.client == caller) {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if escrow_data.state != EscrowState::Created && !escrow_data.state.is_active() {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    // Milestones may have moved on since the proposal was made
+    let terms = proposal.terms.clone();
+    validate_amendment(env, &escrow_data, &terms);
+
+    let previous_amount = escrow_data.amount;
+    let mut settled = 0;
+    if escrow_data.state.is_active() {
+        settled = terms.amount - previous_amount;
+        if settled > proposal.deposited {
+            // Nothing was held at proposal time (the escrow was not funded yet), so
+            // the client signs for the top-up alongside the accepting payee
+            if caller != escrow_data.client {
+                escrow_data.client.require_auth();
+            }
+            collect_deposit(env, &escrow_data, &escrow_data.client, settled - proposal.deposited);
+        } else if settled < 0 {
+            return_to_client(env, &escrow_data, -settled);
+        }
+    }
+
+    escrow_data.amount = terms.amount;
+    escrow_data.funded_amount += settled;
+    escrow_data.net_amount = escrow_data.amount - escrow_data.fee_collected;
+    if terms.timeout_secs.is_some() {
+        escrow_data.timeout_secs = terms.timeout_secs;
+    }
+    if terms.replace_milestones {
+        apply_milestone_terms(env, &mut escrow_data, &terms.milestones);
+    }
+    if escrow_data.state.is_active() && remaining_balance(&escrow_data) == 0 {
+        transition_state(env, &mut escrow_data, EscrowState::Completed);
+    }
+
+    save_escrow(env, &escrow_data);
+    remove_amendment(env, escrow_id);
+
+    let mut versions = load_versions(env, escrow_id);
+    let version = EscrowVersion {
+        version: versions.len() + 1,
+        proposed_by: proposal.proposed_by,
+        approved_by: caller.clone(),
+        previous_amount,
+        amount: escrow_data.amount,
+        timeout_secs: escrow_data.timeout_secs,
+        milestone_count: escrow_data.milestones.len(),
+        settled,
+        timestamp: env.ledger().timestamp(),
+    };
+    versions.push_back(version.clone());
+    save_versions(env, escrow_id, &versions);
+    audit(env, &escrow_data, AuditAction::AmendmentAccepted, &caller, before, settled);
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (Symbol::new(env, "amendment_accepted"), caller),
+        (escrow_id, version.version, previous_amount, version.amount, settled, version.timestamp),
+    );
+}
+
+// Either party drops the pending amendment; an escrowed top-up goes back to the client.
+pub fn cancel_amendment(env: &Env, escrow_id: u32, caller: Address) {
+    caller.require_auth();
+
+    let escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+
+    if escrow_data.client != caller && !is_payee(&escrow_data, &caller) {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    let proposal = load_amendment(env, escrow_id)
+        .unwrap_or_else(|| handle_error(env, Error::AmendmentNotFound));
+
+    return_to_client(env, &escrow_data, proposal.deposited);
+    remove_amendment(env, escrow_id);
+    audit(env, &escrow_data, AuditAction::AmendmentCancelled, &caller, before, proposal.deposited);
+
+    env.events().publish(
+        (Symbol::new(env, "amendment_cancelled"), caller),
+        (escrow_id, proposal.deposited, env.ledger().timestamp()),
+    );
+}
+
+pub fn get_amendment(env: &Env, escrow_id: u32) -> Option<AmendmentProposal> {
+    load_amendment(env, escrow_id)
+}
+
+pub fn get_escrow_versions(env: &Env, escrow_id: u32) -> Vec<EscrowVersion> {
+    load_versions(env, escrow_id)
+}
+
+// ==================== TIPS ====================
+
+// The client sends an extra `amount` to the freelancer side of a settled escrow.
+// Tips are priced by the fee manager's tip rate and kept out of the escrow's own
+// amount, fee and team accounting.
+pub fn tip(env: &Env, escrow_id: u32, client: Address, amount: i128) {
+    client.require_auth();
+
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+
+    if escrow_data.client != client {
+        handle_error(env, Error::Unauthorized);
+    }
+    if !matches!(escrow_data.state, EscrowState::Released | EscrowState::Completed) {
+        handle_error(env, Error::InvalidStatus);
+    }
+    if escrow_data.token.is_none() {
+        handle_error(env, Error::InvalidStatus);
+    }
+    if let Err(e) = validate_amount(amount) {
+        handle_error(env, e);
+    }
+
+    collect_deposit(env, &escrow_data, &client, amount);
+
+    let mut payout = escrow_data.clone();
+    pay_freelancers(env, &mut payout, amount, FEE_CALCULATE_TIP);
+    let fee = payout.fee_collected - escrow_data.fee_collected;
+    // Team members' totals include their tips; the escrow's own fee accounting doesn't
+    escrow_data.team = payout.team;
+    save_escrow(env, &escrow_data);
+
+    let record = TipRecord {
+        from: client.clone(),
+        amount,
+        fee,
+        timestamp: env.ledger().timestamp(),
+    };
+    let mut tips = load_tips(env, escrow_id);
+    tips.push_back(record.clone());
+    save_tips(env, escrow_id, &tips);
+    audit(env, &escrow_data, AuditAction::Tipped, &client, before, amount);
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (Symbol::new(env, "escrow_tipped"), client, escrow_data.freelancer),
+        (escrow_id, amount, fee, record.timestamp),
+    );
+}
+
+pub fn get_tips(env: &Env, escrow_id: u32) -> Vec<TipRecord> {
+    load_tips(env, escrow_id)
+}
+
+// ==================== POST-RELEASE HOLDBACK ====================
+
+pub fn get_holdback(env: &Env, escrow_id: u32) -> Holdback {
+    load_holdback(env, escrow_id).unwrap_or_else(|| handle_error(env, Error::HoldbackNotFound))
+}
+
+// The client contests the delivered work during the grace window. The claim is
+// capped at the holdback and freezes it until the arbitrator rules on it.
+pub fn open_holdback_claim(
+    env: &Env,
+    escrow_id: u32,
+    client: Address,
+    amount: i128,
+    reason_hash: BytesN<32>,
+) {
+    client.require_auth();
+
+    let escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+    let mut holdback = get_holdback(env, escrow_id);
+
+    if escrow_data.client != client {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    // Only the arbitrator can resolve a claim; without one it would freeze the holdback
+    if escrow_data.arbitrator.is_none() {
+        handle_error(env, Error::NoArbitrator);
+    }
+
+    if holdback.settled || holdback.claim_amount != 0 {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    if env.ledger().timestamp() >= holdback.expires_at {
+        handle_error(env, Error::GraceWindowExpired);
+    }
+
+    if let Err(e) = validate_amount(amount) {
+        handle_error(env, e);
+    }
+    if amount > holdback.amount {
+        handle_error(env, Error::InvalidAmount);
+    }
+
+    holdback.claim_amount = amount;
+    holdback.claim_hash = Some(reason_hash.clone());
+    save_holdback(env, escrow_id, &holdback);
+    audit(env, &escrow_data, AuditAction::HoldbackClaimed, &client, before, amount);
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (Symbol::new(env, "holdback_claimed"), client),
+        (escrow_id, amount, reason_hash, env.ledger().timestamp()),
+    );
+}
+
+// The arbitrator awards the client up to the claimed amount back, without a fee.
+// The rest of the holdback goes to the freelancer side right away.
+pub fn resolve_holdback_claim(env: &Env, escrow_id: u32, caller: Address, refund_amount: i128) {
+    caller.require_auth();
+
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+    let mut holdback = get_holdback(env, escrow_id);
+
+    if escrow_data.arbitrator != Some(caller.clone()) {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if holdback.settled || holdback.claim_amount == 0 {
+        handle_error(env, Error::DisputeNotOpen);
+    }
+
+    if refund_amount < 0 || refund_amount > holdback.claim_amount {
+        handle_error(env, Error::InvalidAmount);
+    }
+
+    return_to_client(env, &escrow_data, refund_amount);
+    let released = holdback.amount - refund_amount;
+    pay_freelancers(env, &mut escrow_data, released, FEE_CALCULATE_ESCROW);
+
+    holdback.refunded = refund_amount;
+    holdback.settled = true;
+    save_holdback(env, escrow_id, &holdback);
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::HoldbackResolved, &caller, before, holdback.amount);
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (Symbol::new(env, "holdback_claim_resolved"), caller),
+        (escrow_id, refund_amount, released, env.ledger().timestamp()),
+    );
+}
+
+// Once the grace window has passed without a claim, anyone can push the holdback
+// to the freelancer side, in the same way as `auto_release`.
+pub fn release_holdback(env: &Env, escrow_id: u32) {
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+    let mut holdback = get_holdback(env, escrow_id);
+
+    if holdback.settled {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    // An open claim waits for the arbitrator even after the window closes
+    if holdback.claim_amount != 0 {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    if env.ledger().timestamp() < holdback.expires_at {
+        handle_error(env, Error::DeadlineNotReached);
+    }
+
+    pay_freelancers(env, &mut escrow_data, holdback.amount, FEE_CALCULATE_ESCROW);
+
+    holdback.settled = true;
+    save_holdback(env, escrow_id, &holdback);
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::HoldbackReleased, &env.current_contract_address(), before, holdback.amount);
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (Symbol::new(env, "holdback_released"), escrow_data.freelancer),
+        (escrow_id, holdback.amount, env.ledger().timestamp()),
+    );
+}
+
+// ==================== RETAINER ESCROWS ====================
+
+/// Registry mode: a recurring escrow paying `period_amount` for each of
+/// `total_periods` periods of `period_secs`. The deposit covers the first period
+/// and the client tops up later ones ahead of time; `amount` always holds the
+/// funded total.
+pub fn create_retainer_escrow(
+    env: &Env,
+    client: Address,
+    freelancer: Address,
+    arbitrator: Address,
+    token: Address,
+    schedule: RetainerSchedule,
+    fee_manager: Address,
+) -> u32 {
+    let caller = client.clone();
+
+    client.require_auth();
+
+    let RetainerSchedule { period_secs, period_amount, total_periods } = schedule;
+    let term_secs = validate_retainer_terms(period_secs, period_amount, total_periods)
+        .unwrap_or_else(|e| handle_error(env, e));
+    let escrow_id = next_escrow_id(env);
+    // The timeout checks cap a single period at a year; the whole term is set after
+    let mut escrow_data = new_escrow(
+        env,
+        escrow_id,
+        client,
+        freelancer,
+        arbitrator,
+        token,
+        period_amount,
+        period_secs,
+        fee_manager,
+        period_amount * total_periods as i128,
+    );
+    escrow_data.timeout_secs = Some(term_secs);
+    save_escrow(env, &escrow_data);
+    save_retainer(
+        env,
+        escrow_id,
+        &RetainerTerms {
+            period_secs,
+            period_amount,
+            total_periods,
+            released_periods: 0,
+            objected_period: 0,
+            objection_hash: None,
+        },
+    );
+    audit(env, &escrow_data, AuditAction::Created, &escrow_data.client, EscrowState::Created, period_amount);
+
+    env.events().publish(
+        (Symbol::new(env, "retainer_created"), caller),
+        (escrow_id, period_secs, period_amount, total_periods, env.ledger().timestamp()),
+    );
+    escrow_id
+}
+
+pub fn get_retainer(env: &Env, escrow_id: u32) -> RetainerTerms {
+    load_retainer(env, escrow_id).unwrap_or_else(|| handle_error(env, Error::RetainerNotFound))
+}
+
+// Retainers and streams pay out on their own schedule and hash-locked escrows settle
+// by preimage or timelock; whole-balance and milestone payouts don't apply to them
+fn reject_scheduled(env: &Env, escrow_id: u32) {
+    if load_retainer(env, escrow_id).is_some()
+        || load_stream(env, escrow_id).is_some()
+        || load_hash_lock(env, escrow_id).is_some()
+    {
+        handle_error(env, Error::InvalidStatus);
+    }
+}
+
+fn funded_periods(escrow_data: &EscrowData, terms: &RetainerTerms) -> u32 {
+    (escrow_data.amount / terms.period_amount) as u32
+}
+
+// Periods that have fully run since the first deposit
+fn elapsed_periods(env: &Env, escrow_data: &EscrowData, terms: &RetainerTerms) -> u32 {
+    let now = env.ledger().timestamp();
+    let started = escrow_data.funded_at.unwrap_or(now);
+    let elapsed = (now - started) / terms.period_secs;
+    elapsed.min(terms.total_periods as u64) as u32
+}
+
+pub fn top_up_retainer(env: &Env, escrow_id: u32, client: Address, periods: u32) {
+    client.require_auth();
+
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+    let terms = get_retainer(env, escrow_id);
+
+    if escrow_data.client != client {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if !escrow_data.state.is_active() {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    if periods == 0 || funded_periods(&escrow_data, &terms) + periods > terms.total_periods {
+        handle_error(env, Error::InvalidAmount);
+    }
+
+    let amount = terms.period_amount * periods as i128;
+    collect_deposit(env, &escrow_data, &client, amount);
+    escrow_data.amount += amount;
+    escrow_data.funded_amount += amount;
+    escrow_data.net_amount = escrow_data.amount - escrow_data.fee_collected;
+
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::RetainerToppedUp, &client, before, amount);
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (Symbol::new(env, "retainer_topped_up"), client),
+        (escrow_id, periods, escrow_data.amount, env.ledger().timestamp()),
+    );
+}
+
+// Pays out every funded period that has fully run. A client objection holds back
+// the objected period and everything after it.
+pub fn claim_retainer_periods(env: &Env, escrow_id: u32, freelancer: Address) {
+    freelancer.require_auth();
+
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+    let mut terms = get_retainer(env, escrow_id);
+
+    if !is_payee(&escrow_data, &freelancer) {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if !escrow_data.state.is_active() {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    let mut claimable = elapsed_periods(env, &escrow_data, &terms)
+        .min(funded_periods(&escrow_data, &terms));
+    if terms.objected_period != 0 {
+        claimable = claimable.min(terms.objected_period - 1);
+    }
+    if claimable <= terms.released_periods {
+        if terms.objected_period != 0 {
+            handle_error(env, Error::PeriodObjected);
+        }
+        handle_error(env, Error::PeriodNotElapsed);
+    }
+
+    let periods = claimable - terms.released_periods;
+    let amount = terms.period_amount * periods as i128;
+    pay_freelancers(env, &mut escrow_data, amount, FEE_CALCULATE_ESCROW);
+
+    escrow_data.released_amount += amount;
+    terms.released_periods = claimable;
+
+    if terms.released_periods == terms.total_periods {
+        transition_state(env, &mut escrow_data, EscrowState::Completed);
+    } else if escrow_data.state == EscrowState::Funded {
+        transition_state(env, &mut escrow_data, EscrowState::PartiallyReleased);
+    }
+    save_escrow(env, &escrow_data);
+    save_retainer(env, escrow_id, &terms);
+    audit(env, &escrow_data, AuditAction::RetainerClaimed, &freelancer, before, amount);
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (Symbol::new(env, "retainer_released"), freelancer),
+        (escrow_id, terms.released_periods, amount, env.ledger().timestamp()),
+    );
+}
+
+// The client holds back the running period before it unlocks. The freelancer can
+// take it to the arbitrator with `dispute`, or the client lifts it again.
+pub fn object_retainer_period(env: &Env, escrow_id: u32, client: Address, reason_hash: BytesN<32>) {
+    client.require_auth();
+
+    let escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+    let mut terms = get_retainer(env, escrow_id);
+
+    if escrow_data.client != client {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if !escrow_data.state.is_active() {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    if terms.objected_period != 0 {
+        handle_error(env, Error::PeriodObjected);
+    }
+
+    let period = elapsed_periods(env, &escrow_data, &terms) + 1;
+    if period > funded_periods(&escrow_data, &terms) {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    terms.objected_period = period;
+    terms.objection_hash = Some(reason_hash);
+    save_retainer(env, escrow_id, &terms);
+    audit(env, &escrow_data, AuditAction::RetainerObjected, &client, before, 0);
+
+    env.events().publish(
+        (Symbol::new(env, "retainer_objection"), client),
+        (escrow_id, period, env.ledger().timestamp()),
+    );
+}
+
+pub fn clear_retainer_objection(env: &Env, escrow_id: u32, client: Address) {
+    client.require_auth();
+
+    let escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+    let mut terms = get_retainer(env, escrow_id);
+
+    if escrow_data.client != client {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if terms.objected_period == 0 {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    let period = terms.objected_period;
+    terms.objected_period = 0;
+    terms.objection_hash = None;
+    save_retainer(env, escrow_id, &terms);
+    audit(env, &escrow_data, AuditAction::RetainerObjectionCleared, &client, before, 0);
+
+    env.events().publish(
+        (Symbol::new(env, "retainer_objection_cleared"), client),
+        (escrow_id, period, env.ledger().timestamp()),
+    );
+}
+
+// Either side ends the retainer early. The freelancer is paid for the time worked,
+// pro-rated by the second within the running period; the unused funded time goes
+// back to the client without a fee. The client never got to object to the running
+// period, so its share goes out like a final release, through the holdback window.
+pub fn end_retainer(env: &Env, escrow_id: u32, caller: Address) {
+    caller.require_auth();
+
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+    let terms = get_retainer(env, escrow_id);
+
+    if escrow_data.client != caller && !is_payee(&escrow_data, &caller) {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if !escrow_data.state.is_active() {
+        handle_error(env, Error::InvalidStatus);
+    }
+
+    // A held-back period has to be settled (or lifted) first
+    if terms.objected_period != 0 {
+        handle_error(env, Error::PeriodObjected);
+    }
+
+    let now = env.ledger().timestamp();
+    let worked = now - escrow_data.funded_at.unwrap_or(now);
+    let accrued = (terms.period_amount * worked as i128 / terms.period_secs as i128)
+        .min(escrow_data.amount);
+    let earned = accrued - escrow_data.released_amount;
+    let full_periods = terms.period_amount * elapsed_periods(env, &escrow_data, &terms) as i128;
+    let running = accrued - full_periods.min(accrued);
+
+    pay_freelancers(env, &mut escrow_data, earned - running, FEE_CALCULATE_ESCROW);
+    pay_final_release(env, &mut escrow_data, running);
+    escrow_data.released_amount += earned;
+    let refunded = refund_client_balance(env, &escrow_data);
+
+    if refunded > 0 {
+        transition_state(env, &mut escrow_data, EscrowState::Refunded);
+    } else {
+        transition_state(env, &mut escrow_data, EscrowState::Completed);
+    }
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::RetainerEnded, &caller, before, earned + refunded);
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
+    env.events().publish(
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (Symbol::new(env, "retainer_ended"), caller),
+        (escrow_id, earned, refunded, now),
+    );
+}
+
+// ==================== STREAMING ESCROWS ====================
+
+/// Registry mode: `amount` vests linearly from `start_at` to `end_at` and the
+/// freelancer withdraws whatever has accrued at any time.
+#[allow(clippy::too_many_arguments)]
+pub fn create_stream_escrow(
+    env: &Env,
+    client: Address,
+    freelancer: Address,
+    arbitrator: Address,
+    token: Address,
+    amount: i128,
+    schedule: StreamSchedule,
+    fee_manager: Address,
+) -> u32 {
+    let caller = client.clone();
+
+    client.require_auth();
+
+    if schedule.start_at < env.ledger().timestamp() || schedule.end_at <= schedule.start_at {
+        handle_error(env, Error::InvalidTimestamp);
+    }
+    if let Err(e) = validate_timestamp(env, schedule.end_at) {
+        handle_error(env, e);
+    }
+
+    // Funding stays open until the stream ends, however far off the start is;
+    // the vesting window itself lives in StreamTerms
+    let escrow_id = next_escrow_id(env);
+    let escrow_data = new_escrow(
+        env,
+        escrow_id,
+        client,
+        freelancer,
+        arbitrator,
+        token,
+        amount,
+        schedule.end_at - env.ledger().timestamp(),
+        fee_manager,
+        amount,
+    );
+    save_escrow(env, &escrow_data);
+    save_stream(
+        env,
+        escrow_id,
+        &StreamTerms {
+            start_at: schedule.start_at,
+            end_at: schedule.end_at,
+            paused_at: None,
+            paused_secs: 0,
+        },
+    );
+    audit(env, &escrow_data, AuditAction::Created, &escrow_data.client, EscrowState::Created, amount);
 
-    if !milestone.approved || milestone.released {
+    env.events().publish(
+        (Symbol::new(env, "stream_created"), caller),
+        (escrow_id, amount, schedule.start_at, schedule.end_at),
+    );
+    escrow_id
+}
+
+pub fn get_stream(env: &Env, escrow_id: u32) -> StreamTerms {
+    load_stream(env, escrow_id).unwrap_or_else(|| handle_error(env, Error::StreamNotFound))
+}
+
+// Amount vested so far. Time spent paused does not count, so every pause pushes
+// the end of the stream back by the same amount.
+fn vested_amount(env: &Env, escrow_data: &EscrowData, terms: &StreamTerms) -> i128 {
+    let now = terms.paused_at.unwrap_or(env.ledger().timestamp());
+    let duration = terms.end_at - terms.start_at;
+    let elapsed = now
+        .saturating_sub(terms.start_at)
+        .saturating_sub(terms.paused_secs)
+        .min(duration);
+    escrow_data.amount * elapsed as i128 / duration as i128
+}
+
+pub fn withdraw_vested(env: &Env, escrow_id: u32, freelancer: Address) {
+    freelancer.require_auth();
+
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+    let terms = get_stream(env, escrow_id);
+
+    if !is_payee(&escrow_data, &freelancer) {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if !escrow_data.state.is_active() {
         handle_error(env, Error::InvalidStatus);
     }
 
-    milestone.released = true;
-    milestone.released_at = Some(ts);
-    escrow.released_amount += milestone.amount;
+    let available = vested_amount(env, &escrow_data, &terms) - escrow_data.released_amount;
+    if available <= 0 {
+        handle_error(env, Error::NothingVested);
+    }
 
-    // CORREGIDO: actualizar el milestone en el vector
-    escrow.milestones.set(index, milestone.clone());
+    pay_freelancers(env, &mut escrow_data, available, FEE_CALCULATE_ESCROW);
+    escrow_data.released_amount += available;
 
-    escrow.milestone_history.push_back(MilestoneHistory {
-        milestone: milestone.clone(),
-        action: String::from_str(env, "released"),
-        timestamp: ts,
-    });
-    env.storage().instance().set(&ESCROW_DATA, &escrow);
+    if remaining_balance(&escrow_data) == 0 {
+        transition_state(env, &mut escrow_data, EscrowState::Completed);
+    } else if escrow_data.state == EscrowState::Funded {
+        transition_state(env, &mut escrow_data, EscrowState::PartiallyReleased);
+    }
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::StreamWithdrawn, &freelancer, before, available);
 
     let total_escrow_transaction = increment_escrow_transaction_count(env);
 
@@ -646,59 +2865,97 @@ pub fn release_milestone(env: &Env, freelancer: Address, milestone_id: u32) {
         total_escrow_transaction,
     );
     env.events().publish(
-        (
-            Symbol::new(env, "escrow_milestone_released"),
-            freelancer.clone(),
-        ),
-        (milestone_id, milestone.amount, ts),
+        (Symbol::new(env, "vested_withdrawn"), freelancer),
+        (escrow_id, available, escrow_data.released_amount, env.ledger().timestamp()),
     );
 }
 
-pub fn get_escrow_data(env: &Env) -> EscrowData {
-    if !env.storage().instance().has(&INITIALIZED) {
-        handle_error(env, Error::NotInitialized);
+pub fn pause_stream(env: &Env, escrow_id: u32, client: Address) {
+    client.require_auth();
+
+    let escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+    let mut terms = get_stream(env, escrow_id);
+
+    if escrow_data.client != client {
+        handle_error(env, Error::Unauthorized);
+    }
+
+    if !escrow_data.state.is_active() || terms.paused_at.is_some() {
+        handle_error(env, Error::InvalidStatus);
     }
 
-    env.storage().instance().get(&ESCROW_DATA).unwrap()
+    let now = env.ledger().timestamp();
+    terms.paused_at = Some(now);
+    save_stream(env, escrow_id, &terms);
+    audit(env, &escrow_data, AuditAction::StreamPaused, &client, before, 0);
+
+    env.events().publish(
+        (Symbol::new(env, "stream_paused"), client),
+        (escrow_id, vested_amount(env, &escrow_data, &terms), now),
+    );
 }
 
-pub fn auto_release(env: &Env) {
-    if !env.storage().instance().has(&INITIALIZED) {
-        handle_error(env, Error::NotInitialized);
+pub fn resume_stream(env: &Env, escrow_id: u32, client: Address) {
+    client.require_auth();
+
+    let escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+    let mut terms = get_stream(env, escrow_id);
+
+    if escrow_data.client != client {
+        handle_error(env, Error::Unauthorized);
     }
-    let mut escrow_data: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
-    if escrow_data.state != EscrowState::Funded {
+
+    if !escrow_data.state.is_active() {
         handle_error(env, Error::InvalidStatus);
     }
-    let funded_at = escrow_data.funded_at.unwrap_or(0);
-    let timeout = escrow_data.timeout_secs.unwrap_or(0);
+
     let now = env.ledger().timestamp();
-    
-    // Validate timestamp
-    if let Err(e) = validate_timestamp(env, funded_at + timeout) {
-        handle_error(env, e);
+    let paused_at = terms
+        .paused_at
+        .unwrap_or_else(|| handle_error(env, Error::InvalidStatus));
+    // Only the part of the pause that fell inside the stream delays it
+    terms.paused_secs += now - paused_at.max(terms.start_at).min(now);
+    terms.paused_at = None;
+    save_stream(env, escrow_id, &terms);
+    audit(env, &escrow_data, AuditAction::StreamResumed, &client, before, 0);
+
+    env.events().publish(
+        (Symbol::new(env, "stream_resumed"), client),
+        (escrow_id, terms.paused_secs, now),
+    );
+}
+
+// The client stops the stream for good. What has vested is paid to the freelancer,
+// the unvested remainder goes back to the client without a fee.
+pub fn cancel_stream(env: &Env, escrow_id: u32, client: Address) {
+    client.require_auth();
+
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+    let terms = get_stream(env, escrow_id);
+
+    if escrow_data.client != client {
+        handle_error(env, Error::Unauthorized);
     }
 
-    if now < funded_at + timeout {
+    if !escrow_data.state.is_active() {
         handle_error(env, Error::InvalidStatus);
     }
 
-    if let (Some(token), amount) = (escrow_data.token.clone(), escrow_data.amount) {
-        env.invoke_contract::<()>(
-            &token,
-            &Symbol::new(env, TOKEN_TRANSFER),
-            (
-                env.current_contract_address(),
-                escrow_data.freelancer.clone(),
-                amount,
-            )
-                .into_val(env),
-        );
-    }
-    escrow_data.state = EscrowState::Released;
-    escrow_data.released_at = Some(now);
+    let vested = vested_amount(env, &escrow_data, &terms) - escrow_data.released_amount;
+    pay_freelancers(env, &mut escrow_data, vested, FEE_CALCULATE_ESCROW);
+    escrow_data.released_amount += vested;
+    let refunded = refund_client_balance(env, &escrow_data);
 
-    env.storage().instance().set(&ESCROW_DATA, &escrow_data);
+    if refunded > 0 {
+        transition_state(env, &mut escrow_data, EscrowState::Refunded);
+    } else {
+        transition_state(env, &mut escrow_data, EscrowState::Completed);
+    }
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::StreamCancelled, &client, before, vested + refunded);
 
     let total_escrow_transaction = increment_escrow_transaction_count(env);
 
@@ -707,170 +2964,214 @@ pub fn auto_release(env: &Env) {
         total_escrow_transaction,
     );
     env.events().publish(
-        (
-            Symbol::new(env, "auto_released"),
-            escrow_data.freelancer.clone(),
-        ),
-        (escrow_data.amount, now),
+        (Symbol::new(env, "stream_cancelled"), client),
+        (escrow_id, vested, refunded, env.ledger().timestamp()),
     );
 }
 
-pub fn get_milestones(env: &Env) -> Vec<Milestone> {
-    let escrow: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
-    escrow.milestones.clone()
-}
+// ==================== HASH-LOCKED ESCROWS ====================
 
-pub fn get_milestone_history(env: &Env) -> Vec<MilestoneHistory> {
-    let escrow: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
-    escrow.milestone_history.clone()
+/// Registry mode: a trust-minimized escrow for digital goods. The freelancer opens
+/// it committing to `hash_lock`, the SHA-256 of the deliverable key, and the client
+/// funds it with `deposit_funds`. Revealing the key pays the freelancer; once
+/// `timeout_secs` have passed since funding without a reveal, the client reclaims
+/// the deposit. There is no arbitrator.
+#[allow(clippy::too_many_arguments)]
+pub fn create_htlc_escrow(
+    env: &Env,
+    freelancer: Address,
+    client: Address,
+    token: Address,
+    amount: i128,
+    hash_lock: BytesN<32>,
+    timeout_secs: u64,
+    fee_manager: Address,
+) -> u32 {
+    let caller = freelancer.clone();
+
+    freelancer.require_auth();
+
+    if let Err(e) = validate_init_contract(env, &client, &freelancer, &token, amount, &fee_manager) {
+        handle_error(env, e);
+    }
+    if let Err(e) = validate_timeout(timeout_secs) {
+        handle_error(env, e);
+    }
+    check_token(env, &token);
+    enforce_amount_limits(env, amount);
+
+    let escrow_id = next_escrow_id(env);
+    let escrow_data = EscrowData {
+        escrow_id,
+        client,
+        freelancer,
+        arbitrator: None,
+        token: Some(token),
+        amount,
+        funded_amount: 0,
+        state: EscrowState::Created,
+        dispute_result: DisputeResult::None as u32,
+        created_at: env.ledger().timestamp(),
+        funded_at: None,
+        released_at: None,
+        disputed_at: None,
+        resolved_at: None,
+        timeout_secs: Some(timeout_secs),
+        milestones: Vec::new(env),
+        milestone_history: Vec::new(env),
+        released_amount: 0,
+        fee_manager,
+        fee_collected: 0,
+        net_amount: amount,
+        freelancer_share_bps: 0,
+        freelancer_award: 0,
+        client_award: 0,
+        team: Vec::new(env),
+    };
+    save_escrow(env, &escrow_data);
+    save_hash_lock(
+        env,
+        escrow_id,
+        &HashLockTerms {
+            hash_lock: hash_lock.clone(),
+            preimage: None,
+        },
+    );
+    audit(env, &escrow_data, AuditAction::Created, &escrow_data.client, EscrowState::Created, amount);
+
+    env.events().publish(
+        (Symbol::new(env, "htlc_escrow_created"), caller),
+        (escrow_id, escrow_data.client, amount, hash_lock, timeout_secs),
+    );
+    escrow_id
 }
 
-pub fn set_escrow_data(env: &Env, data: &EscrowData) {
-    env.storage().instance().set(&ESCROW_DATA, data);
+pub fn get_hash_lock(env: &Env, escrow_id: u32) -> HashLockTerms {
+    load_hash_lock(env, escrow_id).unwrap_or_else(|| handle_error(env, Error::HashLockNotFound))
 }
 
-// Logging functions
-pub fn get_call_logs(env: &Env) -> Vec<CallLog> {
-    crate::storage::get_call_logs(env)
+// End of the claim window; the timelock starts when the client funds the escrow
+fn timelock_expiry(escrow_data: &EscrowData) -> u64 {
+    escrow_data.funded_at.unwrap_or(0) + escrow_data.timeout_secs.unwrap_or(0)
 }
 
-pub fn clear_call_logs(env: &Env, caller: Address) {
-    caller.require_auth();
+// The freelancer reveals the deliverable key before the timelock runs out and
+// collects the whole balance. The key is kept on the escrow for the client.
+pub fn claim_htlc(env: &Env, escrow_id: u32, freelancer: Address, preimage: Bytes) {
+    freelancer.require_auth();
+
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+    let mut terms = get_hash_lock(env, escrow_id);
 
-    // Only escrow client can clear logs
-    let escrow: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
-    if escrow.client != caller {
+    if escrow_data.freelancer != freelancer {
         handle_error(env, Error::Unauthorized);
     }
 
-    crate::storage::clear_call_logs(env);
-}
-
+    if escrow_data.state != EscrowState::Funded {
+        handle_error(env, Error::InvalidStatus);
+    }
 
-pub fn set_config(env: &Env, caller: Address, config: ContractConfig) {
-    caller.require_auth();
-    
-    // Only escrow client can set config (admin functionality)
-    let escrow: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
-    if escrow.client != caller { 
-        handle_error(env, Error::Unauthorized); 
+    if env.ledger().timestamp() >= timelock_expiry(&escrow_data) {
+        handle_error(env, Error::TimelockExpired);
     }
-    
-    // Validate config parameters
-    if let Err(e) = validate_config(&config) {
-        handle_error(env, e);
+
+    let digest: BytesN<32> = env.crypto().sha256(&preimage).into();
+    if digest != terms.hash_lock {
+        handle_error(env, Error::InvalidPreimage);
     }
-    
-    env.storage().instance().set(&CONTRACT_CONFIG, &config);
-    
+
+    let remaining = remaining_balance(&escrow_data);
+    pay_freelancers(env, &mut escrow_data, remaining, FEE_CALCULATE_ESCROW);
+    escrow_data.released_amount += remaining;
+    transition_state(env, &mut escrow_data, EscrowState::Released);
+    save_escrow(env, &escrow_data);
+
+    terms.preimage = Some(preimage.clone());
+    save_hash_lock(env, escrow_id, &terms);
+    audit(env, &escrow_data, AuditAction::HtlcClaimed, &freelancer, before, remaining);
+    register_completion(env, &escrow_data);
+
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
+
     env.events().publish(
-        (Symbol::new(env, "cfg_upd"), caller),
-        (config.min_escrow_amount, config.max_escrow_amount, config.default_timeout_days),
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (Symbol::new(env, "htlc_claimed"), freelancer),
+        (escrow_id, preimage, remaining, env.ledger().timestamp()),
     );
 }
 
-pub fn get_config(env: &Env) -> ContractConfig {
-    if !env.storage().instance().has(&CONTRACT_CONFIG) {
-        handle_error(env, Error::NotInitialized);
-    }
-    env.storage().instance().get(&CONTRACT_CONFIG).unwrap()
-}
+// Nothing was revealed before the timelock ran out: the client takes the deposit
+// back without a fee.
+pub fn reclaim_htlc(env: &Env, escrow_id: u32, client: Address) {
+    client.require_auth();
 
-// Helper function to validate config parameters
-fn validate_config(config: &ContractConfig) -> Result<(), Error> {
-    // Validate escrow amounts
-    if config.min_escrow_amount >= config.max_escrow_amount {
-        return Err(Error::InvalidAmount);
-    }
-    
-    if config.min_escrow_amount < 1 {
-        return Err(Error::InvalidAmount);
-    }
-    
-    // Validate timeout (1-365 days)
-    if config.default_timeout_days < 1 || config.default_timeout_days > 365 {
-        return Err(Error::InvalidAmount);
-    }
-    
-    // Validate max milestones (1-100)
-    if config.max_milestones < 1 || config.max_milestones > 100 {
-        return Err(Error::InvalidAmount);
-    }
-    
-    // Validate fee percentage (0-10%)
-    if config.fee_percentage > 1000 {
-        return Err(Error::InvalidAmount);
-    }
-    
-    // Validate rate limit parameters
-    if config.rate_limit_window_hours < 1 || config.rate_limit_window_hours > 168 {
-        return Err(Error::InvalidAmount);
+    let mut escrow_data = load_escrow(env, escrow_id);
+    let before = escrow_data.state.clone();
+    get_hash_lock(env, escrow_id);
+
+    if escrow_data.client != client {
+        handle_error(env, Error::Unauthorized);
     }
-    
-    if config.rate_limit_calls < 1 || config.rate_limit_calls > 1000 {
-        return Err(Error::InvalidAmount);
+
+    if escrow_data.state != EscrowState::Funded {
+        handle_error(env, Error::InvalidStatus);
     }
-    
-    Ok(())
-}
 
-pub fn get_total_transactions(env: &Env) -> u64 {
-    crate::storage::get_total_transactions(env)
-}
+    if env.ledger().timestamp() < timelock_expiry(&escrow_data) {
+        handle_error(env, Error::DeadlineNotReached);
+    }
 
-pub fn reset_transaction_count(env: &Env, admin: Address) -> Result<(), Error> {
-    admin.require_auth();
-    let escrow_data: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
+    let refunded = refund_client_balance(env, &escrow_data);
+    transition_state(env, &mut escrow_data, EscrowState::Refunded);
+    save_escrow(env, &escrow_data);
+    audit(env, &escrow_data, AuditAction::HtlcReclaimed, &client, before, refunded);
 
-    if escrow_data.client != admin {
-        handle_error(env, Error::Unauthorized);
-    }
-    set_escrow_transaction_count(&env, 0u64);
+    let total_escrow_transaction = increment_escrow_transaction_count(env);
 
     env.events().publish(
-        (
-            Symbol::new(env, "transaction_count_reset"),
-            escrow_data.client.clone(),
-        ),
-        env.ledger().timestamp(),
+        (Symbol::new(env, "escrow_tx_count"),),
+        total_escrow_transaction,
+    );
+    env.events().publish(
+        (Symbol::new(env, "htlc_reclaimed"), client),
+        (escrow_id, refunded, env.ledger().timestamp()),
     );
-    Ok(())
 }
 
 // ==================== DATA EXPORT FUNCTIONS ====================
 
 /// Export escrow data (client, freelancer, or arbitrator can access)
-pub fn export_escrow_data(env: &Env, caller: Address, contract_id: String) -> EscrowDataExport {
-    let caller_addr = caller.clone();
-
-    // Log function call start
-    log_function_call(env, "export_escrow_data", &caller_addr, true);
-
+pub fn export_escrow_data(env: &Env, escrow_id: u32, caller: Address, contract_id: String) -> EscrowDataExport {
     caller.require_auth();
 
-    if !env.storage().instance().has(&INITIALIZED) {
-        handle_error(env, Error::NotInitialized);
-    }
-
-    let escrow_data: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
+    let escrow_data = load_escrow(env, escrow_id);
 
     // Permission check: client, freelancer, or arbitrator can export data
     let is_authorized = escrow_data.client == caller
-        || escrow_data.freelancer == caller
+        || is_payee(&escrow_data, &caller)
         || escrow_data.arbitrator == Some(caller.clone());
 
     if !is_authorized {
         handle_error(env, Error::Unauthorized);
     }
 
-    let milestones = get_milestones(env);
-    let milestone_history = get_milestone_history(env);
+    let milestones = get_milestones(env, escrow_id);
+    let milestone_history = get_milestone_history(env, escrow_id);
 
     let export_data = EscrowDataExport {
         contract_id,
         escrow_data: escrow_data.clone(),
         milestones,
         milestone_history,
+        team: escrow_data.team.clone(),
+        versions: load_versions(env, escrow_id),
+        tips: load_tips(env, escrow_id),
+        audit_log: load_audit_entries(env, escrow_id, 0, MAX_AUDIT_PAGE),
+        audit_count: crate::storage::get_audit_count(env, escrow_id),
         export_timestamp: env.ledger().timestamp(),
         export_version: String::from_str(env, "1.0"),
     };
@@ -884,12 +3185,8 @@ pub fn export_escrow_data(env: &Env, caller: Address, contract_id: String) -> Es
     export_data
 }
 
-pub fn get_contract_status(env: &Env, contract_id: Address) -> EscrowSummary {
-    if !env.storage().instance().has(&INITIALIZED) {
-        handle_error(env, Error::NotInitialized);
-    }
-
-    let escrow_data: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
+pub fn get_contract_status(env: &Env, escrow_id: u32, contract_id: Address) -> EscrowSummary {
+    let escrow_data = load_escrow(env, escrow_id);
 
     // Format Escrow Status
     let escrow_data_status = match escrow_data.state {
@@ -897,16 +3194,31 @@ pub fn get_contract_status(env: &Env, contract_id: Address) -> EscrowSummary {
         EscrowState::Funded => String::from_str(&env, "Funded"),
         EscrowState::Released => String::from_str(&env, "Released"),
         EscrowState::Disputed => String::from_str(&env, "Disputed"),
-        EscrowState::Refunded => String::from_str(&env, "Resolved"),
+        EscrowState::Refunded => String::from_str(env, "Refunded"),
+        EscrowState::Cancelled => String::from_str(env, "Cancelled"),
+        EscrowState::PartiallyReleased => String::from_str(env, "PartiallyReleased"),
+        EscrowState::Completed => String::from_str(env, "Completed"),
+        EscrowState::Resolved => String::from_str(env, "Resolved"),
+        EscrowState::PartiallyFunded => String::from_str(env, "PartiallyFunded"),
+    };
+
+    let (vested, stream_paused) = match load_stream(env, escrow_id) {
+        Some(terms) => (vested_amount(env, &escrow_data, &terms), terms.paused_at.is_some()),
+        None => (0, false),
     };
 
     let summary = EscrowSummary {
         client: escrow_data.client,
         freelancer: escrow_data.freelancer,
         amount: escrow_data.amount,
+        funded_amount: escrow_data.funded_amount,
         status: escrow_data_status,
         created_at: escrow_data.created_at,
         milestone_count: escrow_data.milestones.len() as u32,
+        team: escrow_data.team,
+        released_amount: escrow_data.released_amount,
+        vested_amount: vested,
+        stream_paused,
     };
 
     // Emit event for status retrieval
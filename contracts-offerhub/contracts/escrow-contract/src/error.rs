@@ -17,6 +17,39 @@ pub enum Error {
     UnexpectedError = 11,
     InvalidTimestamp = 12,
     TimestampTooOld = 13,
+    MilestoneAmountExceedsEscrow = 14,
+    CancellationNotRequested = 15,
+    DeadlineNotReached = 16,
+    WorkAlreadyDelivered = 17,
+    InvalidSplit = 18,
+    InvalidStateTransition = 19,
+    AmountBelowMinimum = 20,
+    AmountAboveMaximum = 21,
+    TooManyMilestones = 22,
+    FeeExceedsLimit = 23,
+    EscrowNotFound = 24,
+    DeliverableNotSubmitted = 25,
+    ReviewPeriodExpired = 26,
+    MilestoneDisputed = 27,
+    InvalidTeamShares = 28,
+    RetainerNotFound = 29,
+    PeriodNotElapsed = 30,
+    PeriodObjected = 31,
+    StreamNotFound = 32,
+    NothingVested = 33,
+    AmendmentNotFound = 34,
+    AmendmentPending = 35,
+    InvalidToken = 36,
+    HashLockNotFound = 37,
+    InvalidPreimage = 38,
+    TimelockExpired = 39,
+    ApprovalKeyNotFound = 40,
+    ApprovalExpired = 41,
+    InvalidNonce = 42,
+    HoldbackNotFound = 43,
+    GraceWindowExpired = 44,
+    RefundWindowClosed = 45,
+    NoArbitrator = 46,
 }
 
 pub fn handle_error(env: &Env, error: Error) -> ! {
@@ -1,7 +1,7 @@
 #![no_std]
 use crate::types::{EscrowSummary};
 use crate::error::Error;
-use soroban_sdk::{contract, contractimpl, Address, Env, String, Symbol, Vec};
+use soroban_sdk::{contract, contractimpl, Address, Bytes, BytesN, Env, String, Symbol, Vec};
 
 mod contract;
 mod error;
@@ -21,28 +21,30 @@ impl EscrowContract {
         env: Env,
         client: Address,
         freelancer: Address,
+        token: Address,
         amount: i128,
         fee_manager: Address,
     ) {
-        contract::init_contract(&env, client, freelancer, amount, fee_manager);
+        contract::init_contract(&env, client, freelancer, token, amount, fee_manager);
     }
 
     pub fn deposit_funds(env: Env, client: Address) {
-        contract::deposit_funds(&env, client);
+        contract::deposit_funds(&env, storage::DEFAULT_ESCROW_ID, client);
     }
 
     pub fn release_funds(env: Env, freelancer: Address) {
-        contract::release_funds(&env, freelancer);
+        contract::release_funds(&env, storage::DEFAULT_ESCROW_ID, freelancer);
     }
 
     pub fn dispute(env: Env, caller: Address) {
-        contract::dispute(&env, caller);
+        contract::dispute(&env, storage::DEFAULT_ESCROW_ID, caller);
     }
 
     pub fn resolve_dispute(env: Env, caller: Address, result: Symbol) {
-        contract::resolve_dispute(&env, caller, result);
+        contract::resolve_dispute(&env, storage::DEFAULT_ESCROW_ID, caller, result);
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn init_contract_full(
         env: Env,
         client: Address,
@@ -51,6 +53,7 @@ impl EscrowContract {
         token: Address,
         amount: i128,
         timeout_secs: u64,
+        fee_manager: Address,
     ) {
         contract::init_contract_full(
             &env,
@@ -60,38 +63,52 @@ impl EscrowContract {
             token,
             amount,
             timeout_secs,
+            fee_manager,
         );
     }
 
     pub fn auto_release(env: Env) {
-        contract::auto_release(&env);
+        contract::auto_release(&env, storage::DEFAULT_ESCROW_ID);
     }
 
     pub fn get_escrow_data(env: Env) -> types::EscrowData {
-        contract::get_escrow_data(&env)
+        contract::get_escrow_data(&env, storage::DEFAULT_ESCROW_ID)
     }
 
     pub fn add_milestone(env: Env, client: Address, desc: String, amount: i128) -> u32 {
-        contract::add_milestone(&env, client, desc, amount)
+        contract::add_milestone(&env, storage::DEFAULT_ESCROW_ID, client, desc, amount, None)
     }
 
     pub fn approve_milestone(env: Env, client: Address, milestone_id: u32) {
-        contract::approve_milestone(&env, client, milestone_id);
+        contract::approve_milestone(&env, storage::DEFAULT_ESCROW_ID, client, milestone_id);
+    }
+
+    pub fn register_approval_key(env: Env, client: Address, public_key: BytesN<32>) {
+        contract::register_approval_key(&env, client, public_key);
+    }
+
+    pub fn get_approval_key(env: Env, client: Address) -> types::ApprovalKey {
+        contract::get_approval_key(&env, client)
     }
 
     pub fn release_milestone(env: Env, freelancer: Address, milestone_id: u32) {
-        contract::release_milestone(&env, freelancer, milestone_id);
+        contract::release_milestone(&env, storage::DEFAULT_ESCROW_ID, freelancer, milestone_id);
+    }
+
+    pub fn tip(env: Env, client: Address, amount: i128) {
+        contract::tip(&env, storage::DEFAULT_ESCROW_ID, client, amount);
     }
 
     pub fn get_milestones(env: Env) -> Vec<types::Milestone> {
-        contract::get_milestones(&env)
+        contract::get_milestones(&env, storage::DEFAULT_ESCROW_ID)
     }
 
     pub fn get_milestone_history(env: Env) -> Vec<types::MilestoneHistory> {
-        contract::get_milestone_history(&env)
+        contract::get_milestone_history(&env, storage::DEFAULT_ESCROW_ID)
     }
+
     pub fn test_set_dispute_result(env: Env, result: u32) {
-        let mut data = contract::get_escrow_data(&env);
+        let mut data = contract::get_escrow_data(&env, storage::DEFAULT_ESCROW_ID);
         data.dispute_result = result;
         contract::set_escrow_data(&env, &data);
     }
@@ -125,12 +142,414 @@ impl EscrowContract {
         contract::get_config(&env)
     }
 
+    pub fn set_rating_contract(env: Env, caller: Address, rating_contract: Address) {
+        contract::set_rating_contract(&env, caller, rating_contract);
+    }
+
+    pub fn get_rating_contract(env: Env) -> Option<Address> {
+        contract::get_rating_contract(&env)
+    }
+
     pub fn get_contract_status(env: &Env, contract_id: Address) -> EscrowSummary {
-        contract::get_contract_status(&env, contract_id)
+        contract::get_contract_status(env, storage::DEFAULT_ESCROW_ID, contract_id)
+
+    }
+
+    pub fn export_escrow_data(env: Env, caller: Address, contract_id: String) -> types::EscrowDataExport {
+        contract::export_escrow_data(&env, storage::DEFAULT_ESCROW_ID, caller, contract_id)
+    }
+
+    // ===== Multi-escrow registry =====
+    // One contract can host many escrows; each operation below addresses an escrow
+    // by the id `create_escrow` returned. The escrow set up by `init_contract*` is
+    // id 0; the calls above are its single-escrow API, the rest go through these.
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_escrow(
+        env: Env,
+        client: Address,
+        freelancer: Address,
+        arbitrator: Address,
+        token: Address,
+        amount: i128,
+        timeout_secs: u64,
+        fee_manager: Address,
+    ) -> u32 {
+        contract::create_escrow(
+            &env,
+            client,
+            freelancer,
+            arbitrator,
+            token,
+            amount,
+            timeout_secs,
+            fee_manager,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_team_escrow(
+        env: Env,
+        client: Address,
+        shares: Vec<types::TeamShare>,
+        arbitrator: Address,
+        token: Address,
+        amount: i128,
+        timeout_secs: u64,
+        fee_manager: Address,
+    ) -> u32 {
+        contract::create_team_escrow(
+            &env,
+            client,
+            shares,
+            arbitrator,
+            token,
+            amount,
+            timeout_secs,
+            fee_manager,
+        )
+    }
+
+    pub fn create_retainer_escrow(
+        env: Env,
+        client: Address,
+        freelancer: Address,
+        arbitrator: Address,
+        token: Address,
+        schedule: types::RetainerSchedule,
+        fee_manager: Address,
+    ) -> u32 {
+        contract::create_retainer_escrow(
+            &env,
+            client,
+            freelancer,
+            arbitrator,
+            token,
+            schedule,
+            fee_manager,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_stream_escrow(
+        env: Env,
+        client: Address,
+        freelancer: Address,
+        arbitrator: Address,
+        token: Address,
+        amount: i128,
+        schedule: types::StreamSchedule,
+        fee_manager: Address,
+    ) -> u32 {
+        contract::create_stream_escrow(
+            &env,
+            client,
+            freelancer,
+            arbitrator,
+            token,
+            amount,
+            schedule,
+            fee_manager,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_htlc_escrow(
+        env: Env,
+        freelancer: Address,
+        client: Address,
+        token: Address,
+        amount: i128,
+        hash_lock: BytesN<32>,
+        timeout_secs: u64,
+        fee_manager: Address,
+    ) -> u32 {
+        contract::create_htlc_escrow(
+            &env,
+            freelancer,
+            client,
+            token,
+            amount,
+            hash_lock,
+            timeout_secs,
+            fee_manager,
+        )
+    }
+
+    pub fn get_escrow_count(env: Env) -> u32 {
+        storage::get_escrow_count(&env)
+    }
+
+    pub fn deposit_funds_by_id(env: Env, escrow_id: u32, client: Address) {
+        contract::deposit_funds(&env, escrow_id, client);
+    }
+
+    pub fn deposit_by_id(env: Env, escrow_id: u32, client: Address, amount: i128) {
+        contract::deposit(&env, escrow_id, client, amount);
+    }
+
+    pub fn release_funds_by_id(env: Env, escrow_id: u32, freelancer: Address) {
+        contract::release_funds(&env, escrow_id, freelancer);
+    }
+
+    pub fn dispute_by_id(env: Env, escrow_id: u32, caller: Address) {
+        contract::dispute(&env, escrow_id, caller);
+    }
+
+    pub fn resolve_dispute_by_id(env: Env, escrow_id: u32, caller: Address, result: Symbol) {
+        contract::resolve_dispute(&env, escrow_id, caller, result);
+    }
 
+    pub fn resolve_dispute_with_split_by_id(
+        env: Env,
+        escrow_id: u32,
+        caller: Address,
+        resolution: types::DisputeResolution,
+    ) {
+        contract::resolve_dispute_with_split(&env, escrow_id, caller, resolution);
+    }
+
+    pub fn auto_release_by_id(env: Env, escrow_id: u32) {
+        contract::auto_release(&env, escrow_id);
     }
 
+    pub fn request_cancellation_by_id(env: Env, escrow_id: u32, caller: Address) {
+        contract::request_cancellation(&env, escrow_id, caller);
+    }
+
+    pub fn approve_cancellation_by_id(env: Env, escrow_id: u32, caller: Address) {
+        contract::approve_cancellation(&env, escrow_id, caller);
+    }
 
+    pub fn refund_client_by_id(env: Env, escrow_id: u32, client: Address) {
+        contract::refund_client(&env, escrow_id, client);
+    }
+
+    pub fn cancel_escrow_by_id(env: Env, escrow_id: u32, client: Address) {
+        contract::cancel_escrow(&env, escrow_id, client);
+    }
+
+    pub fn get_cancellation_request_by_id(env: Env, escrow_id: u32) -> Option<types::CancellationRequest> {
+        contract::get_cancellation_request(&env, escrow_id)
+    }
+
+    pub fn get_escrow_data_by_id(env: Env, escrow_id: u32) -> types::EscrowData {
+        contract::get_escrow_data(&env, escrow_id)
+    }
+
+    pub fn add_milestone_by_id(
+        env: Env,
+        escrow_id: u32,
+        client: Address,
+        desc: String,
+        amount: i128,
+        deadline: Option<u64>,
+    ) -> u32 {
+        contract::add_milestone(&env, escrow_id, client, desc, amount, deadline)
+    }
+
+    pub fn dispute_milestone_by_id(env: Env, escrow_id: u32, caller: Address, milestone_id: u32) {
+        contract::dispute_milestone(&env, escrow_id, caller, milestone_id);
+    }
+
+    pub fn resolve_milestone_dispute_by_id(
+        env: Env,
+        escrow_id: u32,
+        caller: Address,
+        milestone_id: u32,
+        result: Symbol,
+    ) {
+        contract::resolve_milestone_dispute(&env, escrow_id, caller, milestone_id, result);
+    }
+
+    pub fn approve_milestone_by_id(env: Env, escrow_id: u32, client: Address, milestone_id: u32) {
+        contract::approve_milestone(&env, escrow_id, client, milestone_id);
+    }
+
+    pub fn approve_milestone_signed_by_id(
+        env: Env,
+        escrow_id: u32,
+        approval: types::MilestoneApproval,
+        signature: BytesN<64>,
+    ) {
+        contract::approve_milestone_signed(&env, escrow_id, approval, signature);
+    }
+
+    pub fn release_milestone_by_id(env: Env, escrow_id: u32, freelancer: Address, milestone_id: u32) {
+        contract::release_milestone(&env, escrow_id, freelancer, milestone_id);
+    }
+
+    pub fn submit_deliverable_by_id(
+        env: Env,
+        escrow_id: u32,
+        freelancer: Address,
+        milestone_id: u32,
+        content_hash: BytesN<32>,
+        note: String,
+    ) {
+        contract::submit_deliverable(&env, escrow_id, freelancer, milestone_id, content_hash, note);
+    }
+
+    pub fn request_revision_by_id(
+        env: Env,
+        escrow_id: u32,
+        client: Address,
+        milestone_id: u32,
+        reason_hash: BytesN<32>,
+    ) {
+        contract::request_revision(&env, escrow_id, client, milestone_id, reason_hash);
+    }
+
+    pub fn propose_amendment_by_id(
+        env: Env,
+        escrow_id: u32,
+        caller: Address,
+        terms: types::AmendmentTerms,
+    ) {
+        contract::propose_amendment(&env, escrow_id, caller, terms);
+    }
+
+    pub fn accept_amendment_by_id(env: Env, escrow_id: u32, caller: Address) {
+        contract::accept_amendment(&env, escrow_id, caller);
+    }
+
+    pub fn cancel_amendment_by_id(env: Env, escrow_id: u32, caller: Address) {
+        contract::cancel_amendment(&env, escrow_id, caller);
+    }
+
+    pub fn get_amendment_by_id(env: Env, escrow_id: u32) -> Option<types::AmendmentProposal> {
+        contract::get_amendment(&env, escrow_id)
+    }
+
+    pub fn get_escrow_versions_by_id(env: Env, escrow_id: u32) -> Vec<types::EscrowVersion> {
+        contract::get_escrow_versions(&env, escrow_id)
+    }
+
+    pub fn tip_by_id(env: Env, escrow_id: u32, client: Address, amount: i128) {
+        contract::tip(&env, escrow_id, client, amount);
+    }
+
+    pub fn get_tips_by_id(env: Env, escrow_id: u32) -> Vec<types::TipRecord> {
+        contract::get_tips(&env, escrow_id)
+    }
+
+    pub fn get_holdback_by_id(env: Env, escrow_id: u32) -> types::Holdback {
+        contract::get_holdback(&env, escrow_id)
+    }
+
+    pub fn open_holdback_claim_by_id(
+        env: Env,
+        escrow_id: u32,
+        client: Address,
+        amount: i128,
+        reason_hash: BytesN<32>,
+    ) {
+        contract::open_holdback_claim(&env, escrow_id, client, amount, reason_hash);
+    }
+
+    pub fn resolve_holdback_claim_by_id(env: Env, escrow_id: u32, caller: Address, refund_amount: i128) {
+        contract::resolve_holdback_claim(&env, escrow_id, caller, refund_amount);
+    }
+
+    pub fn release_holdback_by_id(env: Env, escrow_id: u32) {
+        contract::release_holdback(&env, escrow_id);
+    }
+
+    pub fn get_milestones_by_id(env: Env, escrow_id: u32) -> Vec<types::Milestone> {
+        contract::get_milestones(&env, escrow_id)
+    }
+
+    pub fn get_milestone_history_by_id(env: Env, escrow_id: u32) -> Vec<types::MilestoneHistory> {
+        contract::get_milestone_history(&env, escrow_id)
+    }
+
+    pub fn get_audit_log_by_id(env: Env, escrow_id: u32, start: u32, limit: u32) -> Vec<types::AuditEntry> {
+        contract::get_audit_log(&env, escrow_id, start, limit)
+    }
+
+    pub fn get_audit_count_by_id(env: Env, escrow_id: u32) -> u32 {
+        contract::get_audit_count(&env, escrow_id)
+    }
+
+    pub fn get_contract_status_by_id(env: Env, escrow_id: u32, contract_id: Address) -> EscrowSummary {
+        contract::get_contract_status(&env, escrow_id, contract_id)
+    }
+
+    // ===== Retainer escrows =====
+
+    pub fn get_retainer(env: Env, escrow_id: u32) -> types::RetainerTerms {
+        contract::get_retainer(&env, escrow_id)
+    }
+
+    pub fn top_up_retainer(env: Env, escrow_id: u32, client: Address, periods: u32) {
+        contract::top_up_retainer(&env, escrow_id, client, periods);
+    }
+
+    pub fn claim_retainer_periods(env: Env, escrow_id: u32, freelancer: Address) {
+        contract::claim_retainer_periods(&env, escrow_id, freelancer);
+    }
+
+    pub fn object_retainer_period(
+        env: Env,
+        escrow_id: u32,
+        client: Address,
+        reason_hash: BytesN<32>,
+    ) {
+        contract::object_retainer_period(&env, escrow_id, client, reason_hash);
+    }
+
+    pub fn clear_retainer_objection(env: Env, escrow_id: u32, client: Address) {
+        contract::clear_retainer_objection(&env, escrow_id, client);
+    }
+
+    pub fn end_retainer(env: Env, escrow_id: u32, caller: Address) {
+        contract::end_retainer(&env, escrow_id, caller);
+    }
+
+    // ===== Streaming escrows =====
+
+    pub fn get_stream(env: Env, escrow_id: u32) -> types::StreamTerms {
+        contract::get_stream(&env, escrow_id)
+    }
+
+    pub fn withdraw_vested(env: Env, escrow_id: u32, freelancer: Address) {
+        contract::withdraw_vested(&env, escrow_id, freelancer);
+    }
+
+    pub fn pause_stream(env: Env, escrow_id: u32, client: Address) {
+        contract::pause_stream(&env, escrow_id, client);
+    }
+
+    pub fn resume_stream(env: Env, escrow_id: u32, client: Address) {
+        contract::resume_stream(&env, escrow_id, client);
+    }
+
+    pub fn cancel_stream(env: Env, escrow_id: u32, client: Address) {
+        contract::cancel_stream(&env, escrow_id, client);
+    }
+
+    // ===== Hash-locked escrows =====
+
+    pub fn get_hash_lock(env: Env, escrow_id: u32) -> types::HashLockTerms {
+        contract::get_hash_lock(&env, escrow_id)
+    }
+
+    pub fn claim_htlc(env: Env, escrow_id: u32, freelancer: Address, preimage: Bytes) {
+        contract::claim_htlc(&env, escrow_id, freelancer, preimage);
+    }
+
+    pub fn reclaim_htlc(env: Env, escrow_id: u32, client: Address) {
+        contract::reclaim_htlc(&env, escrow_id, client);
+    }
+
+    pub fn export_escrow_data_by_id(
+        env: Env,
+        escrow_id: u32,
+        caller: Address,
+        contract_id: String,
+    ) -> types::EscrowDataExport {
+        contract::export_escrow_data(&env, escrow_id, caller, contract_id)
+    }
 }
 
 #[cfg(test)]
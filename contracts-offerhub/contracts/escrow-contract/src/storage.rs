@@ -4,15 +4,33 @@ use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol, Vec,
 pub const ESCROW_DATA: Symbol = symbol_short!("ESCROW");
 pub const INITIALIZED: Symbol = symbol_short!("INIT");
 pub const CONTRACT_CONFIG: Symbol = symbol_short!("CONFIG");
+pub const ADMIN: Symbol = symbol_short!("ADMIN");
+pub const CANCEL_REQUEST: Symbol = symbol_short!("CANCEL");
+pub const RETAINER: Symbol = symbol_short!("RETAINER");
+pub const STREAM: Symbol = symbol_short!("STREAM");
+pub const AMENDMENT: Symbol = symbol_short!("AMEND");
+pub const VERSIONS: Symbol = symbol_short!("VERSIONS");
+pub const TIPS: Symbol = symbol_short!("TIPS");
+pub const HASH_LOCK: Symbol = symbol_short!("HTLC");
+pub const APPROVAL_KEY: Symbol = symbol_short!("APPR_KEY");
+pub const HOLDBACK: Symbol = symbol_short!("HOLDBACK");
+pub const RATING_CONTRACT: Symbol = symbol_short!("RATING");
+
+// Multi-escrow registry keys
+pub const ESCROWS: Symbol = symbol_short!("ESCROWS");
+pub const NEXT_ESCROW_ID: Symbol = symbol_short!("NEXTID");
+pub const DEFAULT_ESCROW_ID: u32 = 0;
+pub const ESCROW_TTL_THRESHOLD: u32 = 17_280;  // ~1 day of ledgers
+pub const ESCROW_TTL_EXTEND_TO: u32 = 518_400; // ~30 days of ledgers
 
 // Rate limit storage keys
 pub const RATE_LIMITS: Symbol = symbol_short!("RLIM");
 pub const RATE_BYPASS: Symbol = symbol_short!("RLBYP");
 
-// Logging storage keys
-pub const CALL_LOGS: Symbol = symbol_short!("LOGS");
-pub const LOG_COUNT: Symbol = symbol_short!("LCOUNT");
-pub const MAX_LOGS: u32 = 100;
+// Audit trail storage keys
+pub const AUDIT_LOG: Symbol = symbol_short!("AUDIT");
+pub const AUDIT_COUNT: Symbol = symbol_short!("AUDIT_N");
+pub const MAX_AUDIT_PAGE: u32 = 50;
 
 
 // Default configuration values
@@ -23,6 +41,11 @@ pub const DEFAULT_MAX_MILESTONES: u32 = 20;              // Maximum 20 milestone
 pub const DEFAULT_FEE_PERCENTAGE: i128 = 250;           // 2.5% fee
 pub const DEFAULT_RATE_LIMIT_CALLS: u32 = 10;           // 10 calls per window
 pub const DEFAULT_RATE_LIMIT_WINDOW_HOURS: u32 = 1;     // 1 hour window
+pub const DEFAULT_REVIEW_PERIOD_SECS: u64 = 259_200;   // 3 days
+pub const REFUND_WINDOW_SECS: u64 = 259_200;           // 3 days for the client to refund undelivered work
+pub const DEFAULT_MAX_REVISIONS: u32 = 3;               // 3 revision rounds per milestone
+pub const DEFAULT_HOLDBACK_BPS: u32 = 0;                // No holdback unless configured
+pub const DEFAULT_HOLDBACK_SECS: u64 = 604_800;         // 7 days
 
 pub const TOTAL_ESCROW_COUNT: Symbol = symbol_short!("ESCCOUNT");
 
@@ -34,17 +57,6 @@ pub struct RateLimitEntry {
     pub window_start: u64,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
-#[contracttype]
-pub struct CallLog {
-    pub function_name: String,
-    pub caller: Address,
-    pub timestamp: u64,
-    pub success: bool,
-    pub gas_used: u64,
-    pub transaction_hash: String,
-}
-
 fn rl_key(user: &Address, kind: &String) -> (Symbol, Address, String) {
     (RATE_LIMITS, user.clone(), kind.clone())
 }
@@ -107,55 +119,46 @@ pub fn reset_rate_limit(env: &Env, user: &Address, kind: &String) {
     env.storage().persistent().set(&rl_key(user, kind), &entry);
 }
 
-// Logging functions
-pub fn add_call_log(env: &Env, log: &CallLog) {
-    let mut count = env.storage().instance().get(&LOG_COUNT).unwrap_or(0);
-
-    // Rotate logs if we exceed MAX_LOGS
-    if count >= MAX_LOGS {
-        // Remove oldest log (index 0) and shift all logs down
-        for i in 0..count - 1 {
-            if let Some(next_log) = env
-                .storage()
-                .instance()
-                .get::<(Symbol, u32), CallLog>(&(CALL_LOGS, i + 1))
-            {
-                env.storage().instance().set(&(CALL_LOGS, i), &next_log);
-            }
-        }
-        count = MAX_LOGS - 1;
-    }
+// Audit trail: one persistent entry per successful mutation, kept per escrow
+pub fn append_audit_entry(env: &Env, escrow_id: u32, entry: &AuditEntry) {
+    let count_key = (AUDIT_COUNT, escrow_id);
+    let index: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+    let entry_key = (AUDIT_LOG, escrow_id, index);
 
-    // Add new log
-    env.storage().instance().set(&(CALL_LOGS, count), log);
-    env.storage().instance().set(&LOG_COUNT, &(count + 1));
+    env.storage().persistent().set(&entry_key, entry);
+    env.storage().persistent().set(&count_key, &(index + 1));
+    env.storage()
+        .persistent()
+        .extend_ttl(&entry_key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+    env.storage()
+        .persistent()
+        .extend_ttl(&count_key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
 }
 
-pub fn get_call_logs(env: &Env) -> Vec<CallLog> {
-    let count = env.storage().instance().get(&LOG_COUNT).unwrap_or(0);
-    let mut logs = Vec::new(env);
+pub fn get_audit_count(env: &Env, escrow_id: u32) -> u32 {
+    env.storage().persistent().get(&(AUDIT_COUNT, escrow_id)).unwrap_or(0)
+}
 
-    for i in 0..count {
-        if let Some(log) = env
-            .storage()
-            .instance()
-            .get::<(Symbol, u32), CallLog>(&(CALL_LOGS, i))
-        {
-            logs.push_back(log);
+// Entries `start..start + limit`, oldest first
+pub fn load_audit_entries(env: &Env, escrow_id: u32, start: u32, limit: u32) -> Vec<AuditEntry> {
+    let end = get_audit_count(env, escrow_id).min(start.saturating_add(limit));
+    let mut entries = Vec::new(env);
+
+    for index in start..end {
+        let entry_key = (AUDIT_LOG, escrow_id, index);
+        if let Some(entry) = env.storage().persistent().get::<_, AuditEntry>(&entry_key) {
+            env.storage()
+                .persistent()
+                .extend_ttl(&entry_key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+            entries.push_back(entry);
         }
     }
 
-    logs
+    entries
 }
 
-pub fn clear_call_logs(env: &Env) {
-    let count = env.storage().instance().get(&LOG_COUNT).unwrap_or(0);
-
-    for i in 0..count {
-        env.storage().instance().remove(&(CALL_LOGS, i));
-    }
-
-    env.storage().instance().set(&LOG_COUNT, &0);
+pub fn get_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&ADMIN)
 }
 
 pub fn get_total_transactions(env: &Env) -> u64 {
@@ -177,72 +180,351 @@ pub fn increment_escrow_transaction_count(env: &Env) -> u64 {
 }
 
 // --- Escrow state handling ---
-use crate::types::{EscrowData, EscrowState};
+use crate::types::{AmendmentProposal, ApprovalKey, AuditEntry, CancellationRequest, EscrowData, EscrowVersion, EscrowState, HashLockTerms, Holdback, RetainerTerms, StreamTerms, TipRecord};
 use crate::error::handle_error;
 
-// pub fn set_escrow_state(env: &Env, new_state: EscrowState) {
-//     let mut data: EscrowData = env.storage().instance().get(&ESCROW_DATA).unwrap();
+/// Single gate for every escrow state change: rejects moves the state machine
+/// does not allow, stamps the matching audit timestamp and emits `state_changed`.
+/// The caller is responsible for persisting `data`.
+pub fn transition_state(env: &Env, data: &mut EscrowData, new_state: EscrowState) {
+    if !data.state.can_transition_to(&new_state) {
+        handle_error(env, Error::InvalidStateTransition)
+    }
 
+    let prev = data.state.clone();
+    let now = env.ledger().timestamp();
 
-//     if !data.state.can_transition_to(&new_state) {
-//         handle_error(env, Error::InvalidStatus)
-//     }
+    // Maintain audit timestamps tied to states
+    match new_state {
+        // The timeout clock starts with the first deposit
+        EscrowState::Funded | EscrowState::PartiallyFunded => {
+            data.funded_at.get_or_insert(now);
+        }
+        EscrowState::Released | EscrowState::Completed => data.released_at = Some(now),
+        EscrowState::Refunded | EscrowState::Cancelled | EscrowState::Resolved => {
+            data.resolved_at = Some(now);
+        }
+        EscrowState::Disputed => data.disputed_at = Some(now),
+        EscrowState::Created | EscrowState::PartiallyReleased => {}
+    }
 
-//     data.state = new_state.clone();
-//     env.storage().instance().set(&ESCROW_DATA, &data);
+    data.state = new_state;
+
+    // Emit event + keep debug log
+    env.events().publish(
+        (Symbol::new(env, "state_changed"), env.current_contract_address(), data.escrow_id),
+        (&prev, &data.state, now),
+    );
 
-//     log!(env, "Escrow state changed to {:?}", new_state);
-// }
+    log!(env, "Escrow state changed from {:?} to {:?}", prev, data.state);
+}
 
-pub fn set_escrow_state(env: &Env, new_state: EscrowState) {
-        let mut data: EscrowData = crate::storage::get_escrow_data(env);
-        // Guard initialization and load
-    
-        if !data.state.can_transition_to(&new_state) {
-            handle_error(env, Error::InvalidStatus)
-        }
-    
-        let prev = data.state;
-        let now = env.ledger().timestamp();
-    
-        // Maintain audit timestamps tied to states
-        match new_state {
-            EscrowState::Funded => data.funded_at = Some(now),
-            EscrowState::Released => {
-                data.released_at = Some(now);
-                data.resolved_at = data.resolved_at.or(Some(now));
-            }
-            EscrowState::Refunded => {
-                data.resolved_at = Some(now);
-            }
-            EscrowState::Disputed => data.disputed_at = Some(now),
-            EscrowState::Created => {}
+pub fn set_escrow_state(env: &Env, escrow_id: u32, new_state: EscrowState) {
+    let mut data: EscrowData = load_escrow(env, escrow_id);
+    transition_state(env, &mut data, new_state);
+    save_escrow(env, &data);
+}
+
+pub fn get_escrow_state(env: &Env, escrow_id: u32) -> EscrowState {
+    load_escrow(env, escrow_id).state
+}
+
+pub fn get_escrow_data(env: &Env) -> EscrowData {
+    load_escrow(env, DEFAULT_ESCROW_ID)
+}
+
+pub fn is_escrow_funded(env: &Env, escrow_id: u32) -> bool {
+    get_escrow_state(env, escrow_id).is_active()
+}
+
+// --- Multi-escrow registry ---
+// The escrow created through `init_contract`/`init_contract_full` keeps living in
+// instance storage under id 0. Escrows added with `create_escrow` get ids from 1
+// upwards and each one is a persistent entry of its own.
+
+fn escrow_key(escrow_id: u32) -> (Symbol, u32) {
+    (ESCROWS, escrow_id)
+}
+fn cancel_key(escrow_id: u32) -> (Symbol, u32) {
+    (CANCEL_REQUEST, escrow_id)
+}
+fn retainer_key(escrow_id: u32) -> (Symbol, u32) {
+    (RETAINER, escrow_id)
+}
+fn stream_key(escrow_id: u32) -> (Symbol, u32) {
+    (STREAM, escrow_id)
+}
+fn amendment_key(escrow_id: u32) -> (Symbol, u32) {
+    (AMENDMENT, escrow_id)
+}
+fn versions_key(escrow_id: u32) -> (Symbol, u32) {
+    (VERSIONS, escrow_id)
+}
+fn tips_key(escrow_id: u32) -> (Symbol, u32) {
+    (TIPS, escrow_id)
+}
+fn holdback_key(escrow_id: u32) -> (Symbol, u32) {
+    (HOLDBACK, escrow_id)
+}
+fn hash_lock_key(escrow_id: u32) -> (Symbol, u32) {
+    (HASH_LOCK, escrow_id)
+}
+
+pub fn next_escrow_id(env: &Env) -> u32 {
+    let id: u32 = env.storage().instance().get(&NEXT_ESCROW_ID).unwrap_or(DEFAULT_ESCROW_ID) + 1;
+    env.storage().instance().set(&NEXT_ESCROW_ID, &id);
+    id
+}
+
+pub fn get_escrow_count(env: &Env) -> u32 {
+    env.storage().instance().get(&NEXT_ESCROW_ID).unwrap_or(DEFAULT_ESCROW_ID)
+}
+
+pub fn load_escrow(env: &Env, escrow_id: u32) -> EscrowData {
+    if escrow_id == DEFAULT_ESCROW_ID {
+        if !env.storage().instance().has(&INITIALIZED) {
+            handle_error(env, Error::NotInitialized);
         }
-    
-        data.state = new_state;
-        env.storage().instance().set(&ESCROW_DATA, &data);
-    
-        // Emit event + keep debug log
-        env.events().publish(
-            (Symbol::new(env, "escrow_state_changed"), env.current_contract_address()),
-            (&prev, &data.state, now),
-        );
+        return env.storage().instance().get(&ESCROW_DATA).unwrap();
+    }
 
-        log!(env, "Escrow state changed from {:?} to {:?}", prev, data.state);
+    let key = escrow_key(escrow_id);
+    let data: EscrowData = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| handle_error(env, Error::EscrowNotFound));
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+    data
+}
+
+pub fn save_escrow(env: &Env, data: &EscrowData) {
+    if data.escrow_id == DEFAULT_ESCROW_ID {
+        env.storage().instance().set(&ESCROW_DATA, data);
+        return;
     }
 
-pub fn get_escrow_state(env: &Env) -> EscrowState {
-    get_escrow_data(env).state
+    let key = escrow_key(data.escrow_id);
+    env.storage().persistent().set(&key, data);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+}
+
+pub fn load_cancellation_request(env: &Env, escrow_id: u32) -> Option<CancellationRequest> {
+    if escrow_id == DEFAULT_ESCROW_ID {
+        return env.storage().instance().get(&CANCEL_REQUEST);
+    }
+    let key = cancel_key(escrow_id);
+    let request: Option<CancellationRequest> = env.storage().persistent().get(&key);
+    if request.is_some() {
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+    }
+    request
 }
 
-pub fn get_escrow_data(env: &Env) -> EscrowData {
-    if !env.storage().instance().has(&INITIALIZED) {
-        handle_error(env, Error::NotInitialized);
+pub fn set_cancellation_request(env: &Env, escrow_id: u32, request: &CancellationRequest) {
+    if escrow_id == DEFAULT_ESCROW_ID {
+        env.storage().instance().set(&CANCEL_REQUEST, request);
+        return;
+    }
+    let key = cancel_key(escrow_id);
+    env.storage().persistent().set(&key, request);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+}
+
+pub fn remove_cancellation_request(env: &Env, escrow_id: u32) {
+    if escrow_id == DEFAULT_ESCROW_ID {
+        env.storage().instance().remove(&CANCEL_REQUEST);
+    } else {
+        env.storage().persistent().remove(&cancel_key(escrow_id));
+    }
+}
+pub fn load_amendment(env: &Env, escrow_id: u32) -> Option<AmendmentProposal> {
+    if escrow_id == DEFAULT_ESCROW_ID {
+        return env.storage().instance().get(&AMENDMENT);
+    }
+    let key = amendment_key(escrow_id);
+    let proposal: Option<AmendmentProposal> = env.storage().persistent().get(&key);
+    if proposal.is_some() {
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
     }
-    
-    env.storage().instance().get(&ESCROW_DATA).unwrap()
+    proposal
 }
 
-pub fn is_escrow_funded(env: &Env) -> bool {
-    get_escrow_state(env) == EscrowState::Funded
-}
\ No newline at end of file
+pub fn set_amendment(env: &Env, escrow_id: u32, proposal: &AmendmentProposal) {
+    if escrow_id == DEFAULT_ESCROW_ID {
+        env.storage().instance().set(&AMENDMENT, proposal);
+        return;
+    }
+    let key = amendment_key(escrow_id);
+    env.storage().persistent().set(&key, proposal);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+}
+
+pub fn remove_amendment(env: &Env, escrow_id: u32) {
+    if escrow_id == DEFAULT_ESCROW_ID {
+        env.storage().instance().remove(&AMENDMENT);
+    } else {
+        env.storage().persistent().remove(&amendment_key(escrow_id));
+    }
+}
+
+pub fn load_versions(env: &Env, escrow_id: u32) -> Vec<EscrowVersion> {
+    let versions = if escrow_id == DEFAULT_ESCROW_ID {
+        env.storage().instance().get(&VERSIONS)
+    } else {
+        env.storage().persistent().get(&versions_key(escrow_id))
+    };
+    versions.unwrap_or(Vec::new(env))
+}
+
+pub fn save_versions(env: &Env, escrow_id: u32, versions: &Vec<EscrowVersion>) {
+    if escrow_id == DEFAULT_ESCROW_ID {
+        env.storage().instance().set(&VERSIONS, versions);
+        return;
+    }
+    let key = versions_key(escrow_id);
+    env.storage().persistent().set(&key, versions);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+}
+
+pub fn load_tips(env: &Env, escrow_id: u32) -> Vec<TipRecord> {
+    let tips = if escrow_id == DEFAULT_ESCROW_ID {
+        env.storage().instance().get(&TIPS)
+    } else {
+        env.storage().persistent().get(&tips_key(escrow_id))
+    };
+    tips.unwrap_or(Vec::new(env))
+}
+
+pub fn save_tips(env: &Env, escrow_id: u32, tips: &Vec<TipRecord>) {
+    if escrow_id == DEFAULT_ESCROW_ID {
+        env.storage().instance().set(&TIPS, tips);
+        return;
+    }
+    let key = tips_key(escrow_id);
+    env.storage().persistent().set(&key, tips);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+}
+
+// Retainer terms only exist for registry escrows created with `create_retainer_escrow`
+pub fn load_retainer(env: &Env, escrow_id: u32) -> Option<RetainerTerms> {
+    let key = retainer_key(escrow_id);
+    let terms: Option<RetainerTerms> = env.storage().persistent().get(&key);
+    if terms.is_some() {
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+    }
+    terms
+}
+
+pub fn save_retainer(env: &Env, escrow_id: u32, terms: &RetainerTerms) {
+    let key = retainer_key(escrow_id);
+    env.storage().persistent().set(&key, terms);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+}
+
+// Approval keys belong to the client rather than to one escrow, so a nonce is
+// never reused across the client's escrows
+pub fn load_approval_key(env: &Env, client: &Address) -> Option<ApprovalKey> {
+    let key = (APPROVAL_KEY, client.clone());
+    let approval_key: Option<ApprovalKey> = env.storage().persistent().get(&key);
+    if approval_key.is_some() {
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+    }
+    approval_key
+}
+
+pub fn save_approval_key(env: &Env, client: &Address, approval_key: &ApprovalKey) {
+    let key = (APPROVAL_KEY, client.clone());
+    env.storage().persistent().set(&key, approval_key);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+}
+
+pub fn load_holdback(env: &Env, escrow_id: u32) -> Option<Holdback> {
+    if escrow_id == DEFAULT_ESCROW_ID {
+        return env.storage().instance().get(&HOLDBACK);
+    }
+    let key = holdback_key(escrow_id);
+    let holdback: Option<Holdback> = env.storage().persistent().get(&key);
+    if holdback.is_some() {
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+    }
+    holdback
+}
+
+pub fn save_holdback(env: &Env, escrow_id: u32, holdback: &Holdback) {
+    if escrow_id == DEFAULT_ESCROW_ID {
+        env.storage().instance().set(&HOLDBACK, holdback);
+        return;
+    }
+    let key = holdback_key(escrow_id);
+    env.storage().persistent().set(&key, holdback);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+}
+
+// Hash locks only exist for registry escrows created with `create_htlc_escrow`
+pub fn load_hash_lock(env: &Env, escrow_id: u32) -> Option<HashLockTerms> {
+    let key = hash_lock_key(escrow_id);
+    let terms: Option<HashLockTerms> = env.storage().persistent().get(&key);
+    if terms.is_some() {
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+    }
+    terms
+}
+
+pub fn save_hash_lock(env: &Env, escrow_id: u32, terms: &HashLockTerms) {
+    let key = hash_lock_key(escrow_id);
+    env.storage().persistent().set(&key, terms);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+}
+
+// Stream terms only exist for registry escrows created with `create_stream_escrow`
+pub fn load_stream(env: &Env, escrow_id: u32) -> Option<StreamTerms> {
+    let key = stream_key(escrow_id);
+    let terms: Option<StreamTerms> = env.storage().persistent().get(&key);
+    if terms.is_some() {
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+    }
+    terms
+}
+
+pub fn save_stream(env: &Env, escrow_id: u32, terms: &StreamTerms) {
+    let key = stream_key(escrow_id);
+    env.storage().persistent().set(&key, terms);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ESCROW_TTL_THRESHOLD, ESCROW_TTL_EXTEND_TO);
+}
@@ -1,9 +1,15 @@
 #![cfg(test)]
+extern crate std;
 
-use crate::types::EscrowState;
+use crate::storage::{DEFAULT_ESCROW_ID, MAX_AUDIT_PAGE, REFUND_WINDOW_SECS};
+use crate::types::{AmendmentTerms, AuditAction, MilestoneApproval, DisputeResolution, EscrowState, FeeCalculation, FeeConfig, MilestoneAction, MilestoneTerms, RetainerSchedule, StreamSchedule, TeamShare};
 use crate::{EscrowContract, EscrowContractClient};
-use soroban_sdk::testutils::{Address as _, Ledger, LedgerInfo};
-use soroban_sdk::{contract, contractimpl, Address, Env, String, Symbol};
+use soroban_sdk::testutils::{Address as _, AuthorizedFunction, Ledger, LedgerInfo};
+use soroban_sdk::{contract, contractimpl, token, vec, Address, Bytes, BytesN, Env, String, Symbol, Vec};
+use fee_manager_contract::{FeeManagerContract, FeeManagerContractClient};
+use rating_contract::{Contract as RatingContract, ContractClient as RatingContractClient};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::xdr::ToXdr;
 
 #[contract]
 pub struct MockTokenContract;
@@ -14,12 +20,102 @@ impl MockTokenContract {
         1000
     }
     pub fn transfer(_env: Env, _from: Address, _to: Address, _amount: i128) {}
+    pub fn allowance(_env: Env, _from: Address, _spender: Address) -> i128 {
+        0
+    }
+    pub fn transfer_from(_env: Env, _spender: Address, _from: Address, _to: Address, _amount: i128) {}
+    pub fn decimals(_env: Env) -> u32 {
+        7
+    }
 }
 
 fn setup_token(env: &Env) -> Address {
     env.register(MockTokenContract, ())
 }
 
+#[contract]
+pub struct MockFeeManagerContract;
+
+#[contractimpl]
+impl MockFeeManagerContract {
+    pub fn calculate_escrow_fee(_env: Env, amount: i128, _user: Address) -> FeeCalculation {
+        FeeCalculation {
+            original_amount: amount,
+            fee_amount: 0,
+            net_amount: amount,
+            fee_percentage: 0,
+            is_premium: false,
+        }
+    }
+    pub fn calculate_dispute_fee(env: Env, amount: i128, user: Address) -> FeeCalculation {
+        Self::calculate_escrow_fee(env, amount, user)
+    }
+    pub fn get_fee_config(env: Env) -> FeeConfig {
+        FeeConfig {
+            escrow_fee_percentage: 0,
+            dispute_fee_percentage: 0,
+            arbitrator_fee_percentage: 0,
+            admin: env.current_contract_address(),
+            platform_wallet: env.current_contract_address(),
+            initialized: true,
+        }
+    }
+}
+
+fn setup_fee_manager(env: &Env) -> Address {
+    env.register(MockFeeManagerContract, ())
+}
+
+// Real SAC token plus an initialized fee manager (2.5% escrow / 5% dispute fee).
+// Returns (token, fee_manager, platform_wallet).
+fn setup_token_and_fee_manager(env: &Env) -> (Address, Address, Address) {
+    let token_admin = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(token_admin).address();
+    let (fee_manager, platform_wallet) = setup_platform_fee_manager(env);
+
+    (token, fee_manager, platform_wallet)
+}
+
+// Initialized fee manager (2.5% escrow / 5% dispute fee). Returns (fee_manager, platform_wallet).
+fn setup_platform_fee_manager(env: &Env) -> (Address, Address) {
+    let fee_admin = Address::generate(env);
+    let platform_wallet = Address::generate(env);
+    let fee_manager = env.register(FeeManagerContract, ());
+    FeeManagerContractClient::new(env, &fee_manager).initialize(&fee_admin, &platform_wallet);
+
+    (fee_manager, platform_wallet)
+}
+
+// Fresh contract, parties and a real SAC token with `mint` credited to the client.
+// Nothing is initialized; `admin` only takes effect once a test calls `initialize_contract`.
+struct Setup<'a> {
+    contract: EscrowContractClient<'a>,
+    admin: Address,
+    client: Address,
+    freelancer: Address,
+    arbitrator: Address,
+    token: token::Client<'a>,
+    fee_manager: Address,
+}
+
+fn setup_parties(env: &Env, mint: i128, fee_manager: Address) -> Setup<'_> {
+    let contract_id = env.register(EscrowContract, ());
+    let client = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(token_admin).address();
+    token::StellarAssetClient::new(env, &token).mint(&client, &mint);
+
+    Setup {
+        contract: EscrowContractClient::new(env, &contract_id),
+        admin: Address::generate(env),
+        client,
+        freelancer: Address::generate(env),
+        arbitrator: Address::generate(env),
+        token: token::Client::new(env, &token),
+        fee_manager,
+    }
+}
+
 fn setup_env() -> Env {
     let env = Env::default();
     env.ledger().with_mut(|l| l.timestamp = 1000);
@@ -38,16 +134,17 @@ fn test_deposit_and_release_token() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500;
     let timeout = 3600; // 1 hour (minimum allowed)
 
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
     contract.deposit_funds(&client);
-    let data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
+    let data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
     assert_eq!(data.state, EscrowState::Funded);
 
     contract.release_funds(&freelancer);
-    let data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
+    let data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
     assert_eq!(data.state, EscrowState::Released);
 }
 
@@ -64,10 +161,11 @@ fn test_insufficient_fund() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 10000;
     let timeout = 3600; // 1 hour (minimum allowed)
 
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
     contract.deposit_funds(&client);
 }
 
@@ -84,11 +182,12 @@ fn test_already_initilaized() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500_000_000;
     let timeout = 3600; // 1 hour (minimum allowed)
 
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
 
 }
 
@@ -105,10 +204,11 @@ fn test_deposit_insufficient_balance() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500_000_000;
     let timeout = 3600; // 1 hour (minimum allowed)
 
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
     contract.deposit_funds(&client);
 }
 
@@ -138,10 +238,11 @@ fn test_resolve_dispute_arbitrator() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500;
     let timeout = 3600; // 1 hour (minimum allowed)
 
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
     contract.deposit_funds(&client);
 
     contract.dispute(&client);
@@ -164,10 +265,11 @@ fn test_resolve_dispute_panics_for_non_arbitrator() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500;
     let timeout = 3600; // 1 hour (minimum allowed)
 
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
     contract.deposit_funds(&client);
     contract.dispute(&client);
 
@@ -188,10 +290,11 @@ fn test_dispute_not_opened() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500;
     let timeout = 3600; // 1 hour (minimum allowed)
 
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
     contract.deposit_funds(&client);
 
     contract.resolve_dispute(&client, &Symbol::new(&env, "client_wins"));
@@ -210,10 +313,11 @@ fn test_invalid_dsipute_result() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500;
     let timeout = 3600; // 1 hour (minimum allowed)
 
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
     contract.deposit_funds(&client);
 
     contract.dispute(&client);
@@ -236,10 +340,11 @@ fn test_resolve_dispute_unauthorized() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500;
     let timeout = 3600; // 1 hour (minimum allowed)
 
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
     contract.deposit_funds(&client);
 
     contract.dispute(&client);
@@ -259,16 +364,18 @@ fn test_auto_release_after_timeout() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500;
     let timeout = 3600; // 1 hour (minimum allowed)
 
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
     contract.deposit_funds(&client);
 
-    env.ledger().with_mut(|l| l.timestamp += timeout + 1);
+    // Nothing was delivered, so the client's refund window comes first
+    env.ledger().with_mut(|l| l.timestamp += timeout + REFUND_WINDOW_SECS);
 
     contract.auto_release();
-    let data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
+    let data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
     assert_eq!(data.state, EscrowState::Released);
 }
 
@@ -285,10 +392,11 @@ fn test_auto_release_panics_if_not_timed_out() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500;
     let timeout = 3600; // 1 hour (minimum allowed)
 
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
     contract.deposit_funds(&client);
 
     contract.auto_release();
@@ -304,10 +412,11 @@ fn test_init_contract_compatibility() {
 
     let client = Address::generate(&env);
     let freelancer = Address::generate(&env);
-    let fee_manager = Address::generate(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let token = setup_token(&env);
 
-    contract.init_contract(&client, &freelancer, &1000, &fee_manager);
-    let data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
+    contract.init_contract(&client, &freelancer, &token, &1000, &fee_manager);
+    let data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
     assert_eq!(data.client, client);
     assert_eq!(data.freelancer, freelancer);
     assert_eq!(data.amount, 1000);
@@ -324,11 +433,12 @@ fn test_milestone_lifecycle() {
 
     let client = Address::generate(&env);
     let freelancer = Address::generate(&env);
-    let fee_manager = Address::generate(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let token = setup_token(&env);
     let amount = 1000;
 
     // Usar init_contract en lugar de init_contract_full
-    contract.init_contract(&client, &freelancer, &amount, &fee_manager);
+    contract.init_contract(&client, &freelancer, &token, &amount, &fee_manager);
     contract.deposit_funds(&client);
 
     // Usar valores más simples para los milestones
@@ -360,22 +470,23 @@ fn test_dispute_resolution() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 1000;
     let timeout = 3600; // 1 hour (minimum allowed)
 
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
     contract.deposit_funds(&client);
 
     contract.dispute(&client);
-    let data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
+    let data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
     assert_eq!(data.state, EscrowState::Disputed);
 
     // Usar símbolo más simple
     let resolution = Symbol::new(&env, "freelancer");
     contract.resolve_dispute(&arbitrator, &resolution);
 
-    let resolved_data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
-    assert_eq!(resolved_data.state, EscrowState::Released);
+    let resolved_data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
+    assert_eq!(resolved_data.state, EscrowState::Resolved);
 }
 
 #[test]
@@ -388,10 +499,11 @@ fn test_basic_authorization() {
 
     let client = Address::generate(&env);
     let freelancer = Address::generate(&env);
-    let fee_manager = Address::generate(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let token = setup_token(&env);
     let amount = 1000;
 
-    contract.init_contract(&client, &freelancer, &amount, &fee_manager);
+    contract.init_contract(&client, &freelancer, &token, &amount, &fee_manager);
     contract.deposit_funds(&client);
 
     let milestone_desc = String::from_str(&env, "Task Description");
@@ -418,16 +530,17 @@ fn test_timeout_functionality() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 1000;
     let timeout = 3600; // 1 hour (minimum allowed)
 
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
     contract.deposit_funds(&client);
 
-    env.ledger().with_mut(|l| l.timestamp = 5000); // After timeout (1000 + 3600 = 4600)
+    env.ledger().with_mut(|l| l.timestamp = 4600 + REFUND_WINDOW_SECS); // After timeout and refund window
 
     contract.auto_release();
-    let data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
+    let data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
     assert_eq!(data.state, EscrowState::Released);
 }
 
@@ -441,10 +554,11 @@ fn test_multiple_milestones() {
 
     let client = Address::generate(&env);
     let freelancer = Address::generate(&env);
-    let fee_manager = Address::generate(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let token = setup_token(&env);
     let amount = 1000;
 
-    contract.init_contract(&client, &freelancer, &amount, &fee_manager);
+    contract.init_contract(&client, &freelancer, &token, &amount, &fee_manager);
     contract.deposit_funds(&client);
 
     let milestone1_id = contract.add_milestone(&client, &String::from_str(&env, "Frontend"), &300);
@@ -482,15 +596,16 @@ fn test_successful_escrow_flow() {
 
     let client = Address::generate(&env);
     let freelancer = Address::generate(&env);
-    let fee_manager = Address::generate(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let token = setup_token(&env);
     let amount = 1000;
 
-    contract.init_contract(&client, &freelancer, &amount, &fee_manager);
-    let initial_data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
+    contract.init_contract(&client, &freelancer, &token, &amount, &fee_manager);
+    let initial_data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
     assert_eq!(initial_data.state, EscrowState::Created);
 
     contract.deposit_funds(&client);
-    let funded_data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
+    let funded_data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
     assert_eq!(funded_data.state, EscrowState::Funded);
 
     let milestone_desc = String::from_str(&env, "Task Description");
@@ -517,11 +632,12 @@ fn test_escrow_data_integrity() {
 
     let client = Address::generate(&env);
     let freelancer = Address::generate(&env);
-    let fee_manager = Address::generate(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let token = setup_token(&env);
     let amount = 1000;
 
-    contract.init_contract(&client, &freelancer, &amount, &fee_manager);
-    let initial_data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
+    contract.init_contract(&client, &freelancer, &token, &amount, &fee_manager);
+    let initial_data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
 
     assert_eq!(initial_data.client, client);
     assert_eq!(initial_data.freelancer, freelancer);
@@ -530,11 +646,11 @@ fn test_escrow_data_integrity() {
     assert_eq!(initial_data.released_amount, 0);
 
     contract.deposit_funds(&client);
-    let funded_data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
+    let funded_data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
     assert_eq!(funded_data.state, EscrowState::Funded);
 
     contract.release_funds(&freelancer);
-    let released_data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
+    let released_data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
 
     assert_eq!(released_data.state, EscrowState::Released);
 }
@@ -562,6 +678,10 @@ fn test_increment_transaction_count() {
     assert_eq!(config.fee_percentage, 250); // DEFAULT_FEE_PERCENTAGE
     assert_eq!(config.rate_limit_calls, 10); // DEFAULT_RATE_LIMIT_CALLS
     assert_eq!(config.rate_limit_window_hours, 1); // DEFAULT_RATE_LIMIT_WINDOW_HOURS
+    assert_eq!(config.review_period_secs, 259_200); // DEFAULT_REVIEW_PERIOD_SECS
+    assert_eq!(config.max_revisions, 3); // DEFAULT_MAX_REVISIONS
+    assert_eq!(config.holdback_bps, 0); // DEFAULT_HOLDBACK_BPS
+    assert_eq!(config.holdback_secs, 604_800); // DEFAULT_HOLDBACK_SECS
 }
 
 #[test]
@@ -576,6 +696,7 @@ fn test_set_config() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500;
     let timeout = 3600;
 
@@ -584,7 +705,7 @@ fn test_set_config() {
     assert_eq!(initial_count, 0);
 
     // Initialize contract (no transaction count increment)
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
 
     // Deposit funds (should increment count)
     contract.deposit_funds(&client);
@@ -624,13 +745,14 @@ fn test_reset_transaction_count() {
     let admin = Address::generate(&env);
     let client = Address::generate(&env);
     let freelancer = Address::generate(&env);
-    let fee_manager = Address::generate(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let token = setup_token(&env);
 
     // Initialize contract first
     contract.initialize_contract(&admin);
     
     // Initialize escrow data so client can set config
-    contract.init_contract(&client, &freelancer, &500, &fee_manager);
+    contract.init_contract(&client, &freelancer, &token, &1000, &fee_manager);
 
     // Create new config
     let new_config = crate::types::ContractConfig {
@@ -641,9 +763,13 @@ fn test_reset_transaction_count() {
         fee_percentage: 300,
         rate_limit_calls: 15,
         rate_limit_window_hours: 2,
+        review_period_secs: 86_400,
+        max_revisions: 3,
+        holdback_bps: 0,
+        holdback_secs: 604_800,
     };
 
-    contract.set_config(&client, &new_config);
+    contract.set_config(&admin, &new_config);
 
     let config = env.as_contract(&contract_id, || crate::contract::get_config(&env));
     assert_eq!(config.min_escrow_amount, 2000);
@@ -668,6 +794,7 @@ fn test_set_config_unauthorized() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500;
     let timeout = 3600;
 
@@ -676,7 +803,7 @@ fn test_set_config_unauthorized() {
     assert_eq!(initial_count, 0);
 
     // Initialize contract (no transaction count increment)
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
 
     // Deposit funds (should increment count)
     contract.deposit_funds(&client);
@@ -703,6 +830,7 @@ fn test_set_config_unauthorized() {
     let final_count = contract.get_total_transactions();
     assert_eq!(final_count, 5);
 
+    contract.initialize_contract(&Address::generate(&env));
     contract.reset_transaction_count(&freelancer);
     let reset_transaction_count = contract.get_total_transactions();
     assert_eq!(reset_transaction_count, 0);
@@ -727,8 +855,9 @@ fn test_reset_transaction_count_failed() {
     // Initialize escrow data for the test
     let client = Address::generate(&env);
     let freelancer = Address::generate(&env);
-    let fee_manager = Address::generate(&env);
-    contract.init_contract(&client, &freelancer, &500, &fee_manager);
+    let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
+    contract.init_contract(&client, &freelancer, &token, &1000, &fee_manager);
 
     let new_config = crate::types::ContractConfig {
         min_escrow_amount: 2000,
@@ -738,6 +867,10 @@ fn test_reset_transaction_count_failed() {
         fee_percentage: 300,
         rate_limit_calls: 15,
         rate_limit_window_hours: 2,
+        review_period_secs: 86_400,
+        max_revisions: 3,
+        holdback_bps: 0,
+        holdback_secs: 604_800,
     };
 
     // This should fail because unauthorized_user is not the client
@@ -747,6 +880,7 @@ fn test_reset_transaction_count_failed() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500;
     let timeout = 3600;
 
@@ -755,7 +889,7 @@ fn test_reset_transaction_count_failed() {
     assert_eq!(initial_count, 0);
 
     // Initialize contract (no transaction count increment)
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
 
     // Deposit funds (should increment count)
     contract.deposit_funds(&client);
@@ -798,6 +932,7 @@ fn test_get_contract_status() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500;
     let timeout = 3600; // 1 hour (minimum allowed)
 
@@ -813,10 +948,10 @@ fn test_get_contract_status() {
         min_persistent_entry_ttl: 0,
         max_entry_ttl: 0,
     });
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
     contract.deposit_funds(&client);
 
-    let data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
+    let data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
     let summary = contract.get_contract_status(&contract_id.clone());
     assert_eq!(summary.client, client);
     assert_eq!(summary.freelancer, freelancer);
@@ -826,7 +961,7 @@ fn test_get_contract_status() {
     assert_eq!(summary.milestone_count, 0);
 
     contract.release_funds(&freelancer);
-    let data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
+    let data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
     let summary = contract.get_contract_status(&contract_id.clone());
     // // Verify the EscrowSummary
     assert_eq!(summary.client, client);
@@ -852,11 +987,12 @@ fn test_init_contract_full_fails() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500_000_000;
     let timeout = 32_000_000; // Too far: 1_000_000 + 32_000_000 > 1_000_000 + 31_536_000
     
     // Fails with Error::InvalidTimestamp
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
 }
 
 #[test]
@@ -873,11 +1009,12 @@ fn test_deposit_funds_fails() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500;
     let timeout = 3600;
     
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
-    let mut data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
+    let mut data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
     data.created_at = 1_000_000;
     let _ = env.as_contract(&contract_id, || crate::contract::set_escrow_data(&env, &data));
     
@@ -899,13 +1036,14 @@ fn test_release_funds_fails() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500;
     let timeout = 3600;
     
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
     contract.deposit_funds(&client);
 
-    let mut data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
+    let mut data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
     data.created_at = 1_000_000;
     let _ = env.as_contract(&contract_id, || crate::contract::set_escrow_data(&env, &data));
     
@@ -927,16 +1065,2422 @@ fn test_auto_release_fails() {
     let freelancer = Address::generate(&env);
     let arbitrator = Address::generate(&env);
     let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
     let amount = 500;
     let timeout = 3600;
     
-    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
     contract.deposit_funds(&client);
 
-    let mut data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env));
+    let mut data = env.as_contract(&contract_id, || crate::contract::get_escrow_data(&env, DEFAULT_ESCROW_ID));
     data.funded_at = Some(1_000_000);
     let _ = env.as_contract(&contract_id, || crate::contract::set_escrow_data(&env, &data));
 
     // Fails with Error::TimestampTooOld
     contract.auto_release();
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_release_funds_routes_fee_to_platform_wallet() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, platform_wallet) = setup_token_and_fee_manager(&env);
+    let amount = 10_000;
+    let timeout = 3600;
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &amount);
+    let token_client = token::Client::new(&env, &token);
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
+    contract.deposit_funds(&client);
+    assert_eq!(token_client.balance(&contract_id), amount);
+
+    contract.release_funds(&freelancer);
+
+    assert_eq!(token_client.balance(&freelancer), 9_750);
+    assert_eq!(token_client.balance(&platform_wallet), 250);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    let data = contract.get_escrow_data();
+    assert_eq!(data.fee_collected, 250);
+    assert_eq!(data.net_amount, 9_750);
+}
+
+#[test]
+fn test_release_funds_premium_freelancer_pays_no_fee() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, platform_wallet) = setup_token_and_fee_manager(&env);
+    let amount = 10_000;
+    let timeout = 3600;
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &amount);
+    FeeManagerContractClient::new(&env, &fee_manager).add_premium_user(&freelancer);
+    let token_client = token::Client::new(&env, &token);
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
+    contract.deposit_funds(&client);
+    contract.release_funds(&freelancer);
+
+    assert_eq!(token_client.balance(&freelancer), amount);
+    assert_eq!(token_client.balance(&platform_wallet), 0);
+    assert_eq!(contract.get_escrow_data().fee_collected, 0);
+}
+
+#[test]
+fn test_release_milestone_withholds_escrow_fee() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, platform_wallet) = setup_token_and_fee_manager(&env);
+    let amount = 10_000;
+    let timeout = 3600;
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &amount);
+    let token_client = token::Client::new(&env, &token);
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
+    contract.deposit_funds(&client);
+
+    let milestone_id = contract.add_milestone(&client, &String::from_str(&env, "Design"), &4_000);
+    contract.approve_milestone(&client, &milestone_id);
+    contract.release_milestone(&freelancer, &milestone_id);
+
+    assert_eq!(token_client.balance(&freelancer), 3_900);
+    assert_eq!(token_client.balance(&platform_wallet), 100);
+    assert_eq!(contract.get_escrow_data().fee_collected, 100);
+}
+
+#[test]
+fn test_dispute_split_withholds_dispute_fee() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, platform_wallet) = setup_token_and_fee_manager(&env);
+    let amount = 10_000;
+    let timeout = 3600;
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &amount);
+    let token_client = token::Client::new(&env, &token);
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
+    contract.deposit_funds(&client);
+    contract.dispute(&client);
+    contract.resolve_dispute(&arbitrator, &Symbol::new(&env, "split"));
+
+    assert_eq!(token_client.balance(&client), 4_750);
+    assert_eq!(token_client.balance(&freelancer), 4_750);
+    assert_eq!(token_client.balance(&platform_wallet), 500);
+    assert_eq!(token_client.balance(&contract_id), 0);
+    assert_eq!(contract.get_escrow_data().fee_collected, 500);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #14)")]
+fn test_add_milestone_exceeding_escrow_amount() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let token = setup_token(&env);
+
+    contract.init_contract(&client, &freelancer, &token, &1000, &fee_manager);
+    contract.add_milestone(&client, &String::from_str(&env, "Frontend"), &600);
+    contract.add_milestone(&client, &String::from_str(&env, "Backend"), &500);
+}
+
+#[test]
+fn test_release_funds_pays_only_remaining_balance() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, platform_wallet) = setup_token_and_fee_manager(&env);
+    let amount = 10_000;
+    let timeout = 3600;
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &amount);
+    let token_client = token::Client::new(&env, &token);
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
+    contract.deposit_funds(&client);
+
+    let milestone_id = contract.add_milestone(&client, &String::from_str(&env, "Design"), &4_000);
+    contract.approve_milestone(&client, &milestone_id);
+    contract.release_milestone(&freelancer, &milestone_id);
+    contract.release_funds(&freelancer);
+
+    assert_eq!(token_client.balance(&freelancer), 9_750);
+    assert_eq!(token_client.balance(&platform_wallet), 250);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    let data = contract.get_escrow_data();
+    assert_eq!(data.released_amount, amount);
+    assert_eq!(data.fee_collected, 250);
+    assert_eq!(data.state, EscrowState::Released);
+}
+
+#[test]
+fn test_releasing_all_milestones_completes_escrow() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, _platform_wallet) = setup_token_and_fee_manager(&env);
+    let amount = 10_000;
+    let timeout = 3600;
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &amount);
+    let token_client = token::Client::new(&env, &token);
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
+    contract.deposit_funds(&client);
+
+    let first = contract.add_milestone(&client, &String::from_str(&env, "Design"), &4_000);
+    let second = contract.add_milestone(&client, &String::from_str(&env, "Build"), &6_000);
+
+    contract.approve_milestone(&client, &first);
+    contract.release_milestone(&freelancer, &first);
+    assert_eq!(contract.get_escrow_data().state, EscrowState::PartiallyReleased);
+
+    contract.approve_milestone(&client, &second);
+    contract.release_milestone(&freelancer, &second);
+
+    let data = contract.get_escrow_data();
+    assert_eq!(data.state, EscrowState::Completed);
+    assert_eq!(data.released_amount, amount);
+    assert_eq!(token_client.balance(&contract_id), 0);
+}
+
+#[test]
+fn test_mutual_cancellation_refunds_client() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, platform_wallet) = setup_token_and_fee_manager(&env);
+    let amount = 10_000;
+    let timeout = 3600;
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &amount);
+    let token_client = token::Client::new(&env, &token);
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
+    contract.deposit_funds(&client);
+
+    contract.request_cancellation_by_id(&DEFAULT_ESCROW_ID, &freelancer);
+    assert_eq!(contract.get_cancellation_request_by_id(&DEFAULT_ESCROW_ID).unwrap().requested_by, freelancer);
+
+    contract.approve_cancellation_by_id(&DEFAULT_ESCROW_ID, &client);
+
+    assert_eq!(token_client.balance(&client), amount);
+    assert_eq!(token_client.balance(&platform_wallet), 0);
+    assert_eq!(token_client.balance(&contract_id), 0);
+    assert_eq!(contract.get_escrow_data().state, EscrowState::Refunded);
+    assert!(contract.get_cancellation_request_by_id(&DEFAULT_ESCROW_ID).is_none());
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #3)")]
+fn test_cancellation_cannot_be_approved_by_requester() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let token = setup_token(&env);
+
+    contract.init_contract(&client, &freelancer, &token, &1000, &fee_manager);
+    contract.deposit_funds(&client);
+
+    contract.request_cancellation_by_id(&DEFAULT_ESCROW_ID, &client);
+    contract.approve_cancellation_by_id(&DEFAULT_ESCROW_ID, &client);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #15)")]
+fn test_approve_cancellation_without_request() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let token = setup_token(&env);
+
+    contract.init_contract(&client, &freelancer, &token, &1000, &fee_manager);
+    contract.deposit_funds(&client);
+
+    contract.approve_cancellation_by_id(&DEFAULT_ESCROW_ID, &freelancer);
+}
+
+#[test]
+fn test_refund_client_after_deadline() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, _platform_wallet) = setup_token_and_fee_manager(&env);
+    let amount = 10_000;
+    let timeout = 3600;
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &amount);
+    let token_client = token::Client::new(&env, &token);
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
+    contract.deposit_funds(&client);
+
+    env.ledger().with_mut(|l| l.timestamp += timeout);
+    contract.refund_client_by_id(&DEFAULT_ESCROW_ID, &client);
+
+    assert_eq!(token_client.balance(&client), amount);
+    assert_eq!(contract.get_escrow_data().state, EscrowState::Refunded);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #16)")]
+fn test_refund_client_before_deadline() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let amount = 500;
+    let timeout = 3600;
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
+    contract.deposit_funds(&client);
+
+    contract.refund_client_by_id(&DEFAULT_ESCROW_ID, &client);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #17)")]
+fn test_refund_client_after_delivery() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let amount = 500;
+    let timeout = 3600;
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
+    contract.deposit_funds(&client);
+    let milestone_id = contract.add_milestone(&client, &String::from_str(&env, "Design"), &200);
+    contract.approve_milestone(&client, &milestone_id);
+
+    env.ledger().with_mut(|l| l.timestamp += timeout);
+    contract.refund_client_by_id(&DEFAULT_ESCROW_ID, &client);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #17)")]
+fn test_refund_client_after_submitted_deliverable() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let timeout = 3600;
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &500, &timeout, &fee_manager);
+    contract.deposit_funds(&client);
+    let milestone_id = contract.add_milestone(&client, &String::from_str(&env, "Design"), &200);
+    contract.submit_deliverable_by_id(&DEFAULT_ESCROW_ID, &freelancer, &milestone_id, &BytesN::from_array(&env, &[1u8; 32]), &String::from_str(&env, ""));
+
+    // Submitted but never reviewed still counts as delivered
+    env.ledger().with_mut(|l| l.timestamp += timeout);
+    contract.refund_client_by_id(&DEFAULT_ESCROW_ID, &client);
+}
+
+#[test]
+fn test_refund_window_precedes_auto_release() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let timeout = 3600;
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &500, &timeout, &fee_manager);
+    contract.deposit_funds(&client);
+
+    // At the deadline only the client can act
+    env.ledger().with_mut(|l| l.timestamp += timeout);
+    assert!(contract.try_auto_release().is_err());
+
+    // Once the refund window has passed only auto-release remains
+    env.ledger().with_mut(|l| l.timestamp += REFUND_WINDOW_SECS);
+    assert!(contract.try_refund_client_by_id(&DEFAULT_ESCROW_ID, &client).is_err());
+    contract.auto_release();
+    assert_eq!(contract.get_escrow_data().state, EscrowState::Released);
+}
+
+#[test]
+fn test_auto_release_at_deadline_after_delivery() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let timeout = 3600;
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &500, &timeout, &fee_manager);
+    contract.deposit_funds(&client);
+    let milestone_id = contract.add_milestone(&client, &String::from_str(&env, "Design"), &200);
+    contract.submit_deliverable_by_id(&DEFAULT_ESCROW_ID, &freelancer, &milestone_id, &BytesN::from_array(&env, &[1u8; 32]), &String::from_str(&env, ""));
+
+    env.ledger().with_mut(|l| l.timestamp += timeout);
+    contract.auto_release();
+    assert_eq!(contract.get_escrow_data().state, EscrowState::Released);
+}
+
+#[test]
+fn test_cancel_unfunded_escrow() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let token = setup_token(&env);
+
+    contract.init_contract(&client, &freelancer, &token, &1000, &fee_manager);
+    contract.cancel_escrow_by_id(&DEFAULT_ESCROW_ID, &client);
+
+    let data = contract.get_escrow_data();
+    assert_eq!(data.state, EscrowState::Cancelled);
+    assert_eq!(data.resolved_at, Some(1000));
+}
+
+#[test]
+fn test_resolve_dispute_with_basis_point_split() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, platform_wallet) = setup_token_and_fee_manager(&env);
+    let amount = 10_000;
+    let timeout = 3600;
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &amount);
+    let token_client = token::Client::new(&env, &token);
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
+    contract.deposit_funds(&client);
+    contract.dispute(&freelancer);
+
+    let resolution = DisputeResolution {
+        freelancer_share_bps: 7_000,
+        awarded_milestones: Vec::new(&env),
+    };
+    contract.resolve_dispute_with_split_by_id(&DEFAULT_ESCROW_ID, &arbitrator, &resolution);
+
+    // 5% dispute fee on each side
+    assert_eq!(token_client.balance(&freelancer), 6_650);
+    assert_eq!(token_client.balance(&client), 2_850);
+    assert_eq!(token_client.balance(&platform_wallet), 500);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    let data = contract.get_escrow_data();
+    assert_eq!(data.freelancer_share_bps, 7_000);
+    assert_eq!(data.freelancer_award, 7_000);
+    assert_eq!(data.client_award, 3_000);
+    assert_eq!(data.dispute_result, crate::types::DisputeResult::Split as u32);
+}
+
+#[test]
+fn test_resolve_dispute_awards_milestone_before_split() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, _platform_wallet) = setup_token_and_fee_manager(&env);
+    let amount = 10_000;
+    let timeout = 3600;
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &amount);
+    let token_client = token::Client::new(&env, &token);
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
+    contract.deposit_funds(&client);
+    let design = contract.add_milestone(&client, &String::from_str(&env, "Design"), &4_000);
+    contract.add_milestone(&client, &String::from_str(&env, "Build"), &6_000);
+    contract.dispute(&client);
+
+    let resolution = DisputeResolution {
+        freelancer_share_bps: 0,
+        awarded_milestones: vec![&env, design],
+    };
+    contract.resolve_dispute_with_split_by_id(&DEFAULT_ESCROW_ID, &arbitrator, &resolution);
+
+    assert_eq!(token_client.balance(&freelancer), 3_800);
+    assert_eq!(token_client.balance(&client), 5_700);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    let milestones = contract.get_milestones();
+    assert!(milestones.get(0).unwrap().released);
+    assert!(!milestones.get(1).unwrap().released);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #18)")]
+fn test_resolve_dispute_rejects_share_above_100_percent() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let amount = 500;
+    let timeout = 3600;
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout, &fee_manager);
+    contract.deposit_funds(&client);
+    contract.dispute(&client);
+
+    let resolution = DisputeResolution {
+        freelancer_share_bps: 10_001,
+        awarded_milestones: Vec::new(&env),
+    };
+    contract.resolve_dispute_with_split_by_id(&DEFAULT_ESCROW_ID, &arbitrator, &resolution);
+}
+
+#[test]
+fn test_client_wins_dispute_resolves_without_release() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &500, &3600, &fee_manager);
+    contract.deposit_funds(&client);
+    contract.dispute(&client);
+    contract.resolve_dispute(&arbitrator, &Symbol::new(&env, "client_wins"));
+
+    let data = contract.get_escrow_data();
+    assert_eq!(data.state, EscrowState::Resolved);
+    assert_eq!(data.released_at, None);
+    assert_eq!(data.resolved_at, Some(1000));
+
+    let summary = contract.get_contract_status(&contract_id);
+    assert_eq!(summary.status, String::from_str(&env, "Resolved"));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #19)")]
+fn test_illegal_state_transition_is_rejected() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let token = setup_token(&env);
+
+    contract.init_contract(&client, &freelancer, &token, &1000, &fee_manager);
+
+    // An unfunded escrow can never jump straight to Released
+    env.as_contract(&contract_id, || {
+        crate::storage::set_escrow_state(&env, DEFAULT_ESCROW_ID, EscrowState::Released)
+    });
+}
+
+fn limits_config() -> crate::types::ContractConfig {
+    crate::types::ContractConfig {
+        min_escrow_amount: 1000,
+        max_escrow_amount: 100_000,
+        default_timeout_days: 7,
+        max_milestones: 2,
+        fee_percentage: 250,
+        rate_limit_calls: 10,
+        rate_limit_window_hours: 1,
+        review_period_secs: 86_400,
+        max_revisions: 3,
+        holdback_bps: 0,
+        holdback_secs: 604_800,
+    }
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #20)")]
+fn test_init_contract_below_configured_minimum() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let token = setup_token(&env);
+
+    contract.initialize_contract(&admin);
+    contract.init_contract(&client, &freelancer, &token, &999, &fee_manager);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #21)")]
+fn test_init_contract_full_above_configured_maximum() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
+
+    contract.initialize_contract(&admin);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &1_000_000_001, &3600, &fee_manager);
+}
+
+#[test]
+fn test_init_contract_uses_configured_default_timeout() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let token = setup_token(&env);
+
+    contract.initialize_contract(&admin);
+    contract.init_contract(&client, &freelancer, &token, &1000, &fee_manager);
+
+    let data = contract.get_escrow_data();
+    assert_eq!(data.timeout_secs, Some(30 * 24 * 60 * 60)); // DEFAULT_TIMEOUT_DAYS
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #22)")]
+fn test_add_milestone_beyond_configured_maximum() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let token = setup_token(&env);
+
+    contract.initialize_contract(&admin);
+    contract.init_contract(&client, &freelancer, &token, &3000, &fee_manager);
+    contract.set_config(&admin, &limits_config());
+
+    contract.add_milestone(&client, &String::from_str(&env, "Design"), &1000);
+    contract.add_milestone(&client, &String::from_str(&env, "Build"), &1000);
+    contract.add_milestone(&client, &String::from_str(&env, "Ship it"), &1000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #10)")]
+fn test_add_milestone_uses_configured_rate_limit() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let fee_manager = setup_fee_manager(&env);
+    let token = setup_token(&env);
+
+    contract.initialize_contract(&admin);
+    contract.init_contract(&client, &freelancer, &token, &3000, &fee_manager);
+    let mut config = limits_config();
+    config.rate_limit_calls = 1;
+    contract.set_config(&admin, &config);
+
+    contract.add_milestone(&client, &String::from_str(&env, "Design"), &1000);
+    contract.add_milestone(&client, &String::from_str(&env, "Build"), &1000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #23)")]
+fn test_release_funds_fee_above_configured_percentage() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, _platform_wallet) = setup_token_and_fee_manager(&env);
+    let amount = 10_000;
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &amount);
+
+    contract.initialize_contract(&admin);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &3600, &fee_manager);
+
+    // The fee manager charges 2.5% but the escrow only allows 1%
+    let mut config = limits_config();
+    config.fee_percentage = 100;
+    contract.set_config(&admin, &config);
+
+    contract.deposit_funds(&client);
+    contract.release_funds(&freelancer);
+}
+
+#[test]
+fn test_registry_config_set_by_contract_admin() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let client = Address::generate(&env);
+    let (token, fee_manager, _platform_wallet) = setup_token_and_fee_manager(&env);
+
+    contract.initialize_contract(&admin);
+    contract.create_escrow(&client, &Address::generate(&env), &Address::generate(&env), &token, &10_000, &3600, &fee_manager);
+
+    // No default escrow exists; the registry client has no say over shared config
+    assert!(contract.try_set_config(&client, &limits_config()).is_err());
+    contract.set_config(&admin, &limits_config());
+    assert_eq!(contract.get_config().max_escrow_amount, 100_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #2)")]
+fn test_set_config_requires_initialized_contract() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    contract.set_config(&Address::generate(&env), &limits_config());
+}
+
+#[test]
+fn test_registry_hosts_independent_escrows() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let other_freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, _platform_wallet) = setup_token_and_fee_manager(&env);
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &30_000);
+    let token_client = token::Client::new(&env, &token);
+
+    let first = contract.create_escrow(&client, &freelancer, &arbitrator, &token, &10_000, &3600, &fee_manager);
+    let second = contract.create_escrow(&client, &other_freelancer, &arbitrator, &token, &20_000, &3600, &fee_manager);
+    assert_eq!((first, second), (1, 2));
+    assert_eq!(contract.get_escrow_count(), 2);
+
+    contract.deposit_funds_by_id(&first, &client);
+    contract.deposit_funds_by_id(&second, &client);
+    assert_eq!(token_client.balance(&contract_id), 30_000);
+
+    let milestone = contract.add_milestone_by_id(&second, &client, &String::from_str(&env, "Design"), &5_000, &None);
+    contract.release_funds_by_id(&first, &freelancer);
+
+    assert_eq!(token_client.balance(&freelancer), 9_750);
+    assert_eq!(token_client.balance(&contract_id), 20_000);
+    assert_eq!(contract.get_escrow_data_by_id(&first).state, EscrowState::Released);
+
+    let data = contract.get_escrow_data_by_id(&second);
+    assert_eq!(data.escrow_id, second);
+    assert_eq!(data.state, EscrowState::Funded);
+    assert_eq!(data.milestones.len(), 1);
+    assert_eq!(contract.get_milestones_by_id(&first).len(), 0);
+
+    contract.dispute_by_id(&second, &other_freelancer);
+    contract.resolve_dispute_by_id(&second, &arbitrator, &Symbol::new(&env, "client_wins"));
+    assert_eq!(contract.get_escrow_data_by_id(&second).state, EscrowState::Resolved);
+
+    let export = contract.export_escrow_data_by_id(&second, &client, &String::from_str(&env, "job-2"));
+    assert_eq!(export.escrow_data.escrow_id, second);
+    assert_eq!(export.milestones.get(0).unwrap().id, milestone);
+}
+
+#[test]
+fn test_registry_coexists_with_single_escrow() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &500, &3600, &fee_manager);
+    let escrow_id = contract.create_escrow(&client, &freelancer, &arbitrator, &token, &700, &3600, &fee_manager);
+
+    contract.deposit_funds(&client);
+    assert_eq!(contract.get_escrow_data().state, EscrowState::Funded);
+    assert_eq!(contract.get_escrow_data_by_id(&escrow_id).state, EscrowState::Created);
+
+    contract.cancel_escrow_by_id(&escrow_id, &client);
+    assert_eq!(contract.get_escrow_data_by_id(&escrow_id).state, EscrowState::Cancelled);
+    assert_eq!(contract.get_escrow_data().escrow_id, DEFAULT_ESCROW_ID);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #24)")]
+fn test_registry_unknown_escrow_id() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    contract.deposit_funds_by_id(&7, &client);
+}
+
+fn setup_funded_milestone(env: &Env) -> (EscrowContractClient<'_>, Address, Address, u32) {
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(env, &contract_id);
+
+    let client = Address::generate(env);
+    let freelancer = Address::generate(env);
+    let arbitrator = Address::generate(env);
+    let token = setup_token(env);
+    let fee_manager = setup_fee_manager(env);
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &1000, &3600, &fee_manager);
+    contract.deposit_funds(&client);
+    let milestone_id = contract.add_milestone(&client, &String::from_str(env, "Landing page"), &400);
+
+    (contract, client, freelancer, milestone_id)
+}
+
+#[test]
+fn test_deliverable_auto_approves_after_review_period() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (contract, _client, freelancer, milestone_id) = setup_funded_milestone(&env);
+
+    let content_hash = BytesN::from_array(&env, &[7u8; 32]);
+    let note = String::from_str(&env, "ipfs bundle v1");
+    contract.submit_deliverable_by_id(&DEFAULT_ESCROW_ID, &freelancer, &milestone_id, &content_hash, &note);
+
+    let milestone = contract.get_milestones().get(0).unwrap();
+    assert_eq!(milestone.deliverable_hash, Some(content_hash));
+    assert_eq!(milestone.deliverable_note, Some(note));
+    assert_eq!(milestone.submitted_at, Some(1000));
+
+    // Client stays silent for the whole default review window (3 days)
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 259_200);
+    contract.release_milestone(&freelancer, &milestone_id);
+
+    let milestone = contract.get_milestones().get(0).unwrap();
+    assert!(milestone.approved && milestone.released);
+
+    let mut actions = Vec::new(&env);
+    for entry in contract.get_milestone_history().iter() {
+        actions.push_back(entry.action);
+    }
+    assert_eq!(
+        actions,
+        vec![
+            &env,
+            MilestoneAction::Added,
+            MilestoneAction::Submitted,
+            MilestoneAction::AutoApproved,
+            MilestoneAction::Released,
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #6)")]
+fn test_deliverable_not_releasable_during_review_period() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (contract, _client, freelancer, milestone_id) = setup_funded_milestone(&env);
+
+    let content_hash = BytesN::from_array(&env, &[7u8; 32]);
+    contract.submit_deliverable_by_id(&DEFAULT_ESCROW_ID, &freelancer, &milestone_id, &content_hash, &String::from_str(&env, ""));
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 259_199);
+    contract.release_milestone(&freelancer, &milestone_id);
+}
+
+#[test]
+fn test_request_revision_reopens_milestone() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (contract, client, freelancer, milestone_id) = setup_funded_milestone(&env);
+
+    let first = BytesN::from_array(&env, &[1u8; 32]);
+    let second = BytesN::from_array(&env, &[2u8; 32]);
+    contract.submit_deliverable_by_id(&DEFAULT_ESCROW_ID, &freelancer, &milestone_id, &first, &String::from_str(&env, "draft"));
+    contract.request_revision_by_id(&DEFAULT_ESCROW_ID, &client, &milestone_id, &BytesN::from_array(&env, &[9u8; 32]));
+
+    let milestone = contract.get_milestones().get(0).unwrap();
+    assert_eq!(milestone.submitted_at, None);
+    assert!(!milestone.approved);
+
+    env.ledger().with_mut(|l| l.timestamp = 2000);
+    contract.submit_deliverable_by_id(&DEFAULT_ESCROW_ID, &freelancer, &milestone_id, &second, &String::from_str(&env, "final"));
+    contract.approve_milestone(&client, &milestone_id);
+    contract.release_milestone(&freelancer, &milestone_id);
+
+    let milestone = contract.get_milestones().get(0).unwrap();
+    assert_eq!(milestone.deliverable_hash, Some(second));
+    assert_eq!(milestone.submitted_at, Some(2000));
+    assert!(milestone.released);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #25)")]
+fn test_request_revision_without_deliverable() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (contract, client, _freelancer, milestone_id) = setup_funded_milestone(&env);
+
+    contract.request_revision_by_id(&DEFAULT_ESCROW_ID, &client, &milestone_id, &BytesN::from_array(&env, &[9u8; 32]));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #26)")]
+fn test_request_revision_after_review_period() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (contract, client, freelancer, milestone_id) = setup_funded_milestone(&env);
+
+    let content_hash = BytesN::from_array(&env, &[7u8; 32]);
+    contract.submit_deliverable_by_id(&DEFAULT_ESCROW_ID, &freelancer, &milestone_id, &content_hash, &String::from_str(&env, ""));
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 259_200);
+    contract.request_revision_by_id(&DEFAULT_ESCROW_ID, &client, &milestone_id, &BytesN::from_array(&env, &[9u8; 32]));
+}
+
+#[test]
+fn test_revision_rounds_are_counted_and_recorded() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (contract, client, freelancer, milestone_id) = setup_funded_milestone(&env);
+
+    let reason = BytesN::from_array(&env, &[3u8; 32]);
+    for round in 1..=2u8 {
+        let content_hash = BytesN::from_array(&env, &[round; 32]);
+        contract.submit_deliverable_by_id(&DEFAULT_ESCROW_ID, &freelancer, &milestone_id, &content_hash, &String::from_str(&env, ""));
+        contract.request_revision_by_id(&DEFAULT_ESCROW_ID, &client, &milestone_id, &reason);
+    }
+
+    let milestone = contract.get_milestones().get(0).unwrap();
+    assert_eq!(milestone.revision_count, 2);
+
+    let history = contract.get_milestone_history();
+    let last = history.get(history.len() - 1).unwrap();
+    assert_eq!(last.action, MilestoneAction::RevisionRequested);
+    assert_eq!(last.reason_hash, Some(reason));
+    assert_eq!(last.milestone.deliverable_hash, Some(BytesN::from_array(&env, &[2u8; 32])));
+    assert_eq!(contract.get_escrow_data().state, EscrowState::Funded);
+}
+
+#[test]
+fn test_revision_limit_escalates_to_dispute() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
+
+    contract.initialize_contract(&admin);
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &1000, &3600, &fee_manager);
+    let mut config = limits_config();
+    config.max_revisions = 1;
+    contract.set_config(&admin, &config);
+
+    contract.deposit_funds(&client);
+    let milestone_id = contract.add_milestone(&client, &String::from_str(&env, "Landing page"), &400);
+
+    let content_hash = BytesN::from_array(&env, &[1u8; 32]);
+    let reason = BytesN::from_array(&env, &[9u8; 32]);
+    contract.submit_deliverable_by_id(&DEFAULT_ESCROW_ID, &freelancer, &milestone_id, &content_hash, &String::from_str(&env, ""));
+    contract.request_revision_by_id(&DEFAULT_ESCROW_ID, &client, &milestone_id, &reason);
+    contract.submit_deliverable_by_id(&DEFAULT_ESCROW_ID, &freelancer, &milestone_id, &content_hash, &String::from_str(&env, ""));
+    contract.request_revision_by_id(&DEFAULT_ESCROW_ID, &client, &milestone_id, &reason);
+
+    let data = contract.get_escrow_data();
+    assert_eq!(data.state, EscrowState::Disputed);
+    assert_eq!(data.disputed_at, Some(1000));
+
+    let history = contract.get_milestone_history();
+    let last = history.get(history.len() - 1).unwrap();
+    assert_eq!(last.action, MilestoneAction::Escalated);
+    assert_eq!(last.milestone.revision_count, 1);
+
+    contract.resolve_dispute(&arbitrator, &Symbol::new(&env, "split"));
+    assert_eq!(contract.get_escrow_data().state, EscrowState::Resolved);
+}
+
+#[test]
+fn test_milestone_deadline_flags_late_delivery() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (contract, client, freelancer, on_time) = setup_funded_milestone(&env);
+
+    let late = contract.add_milestone_by_id(&DEFAULT_ESCROW_ID, &client, &String::from_str(&env, "Backend"), &300, &Some(5000));
+    let milestone = contract.get_milestones().get(late - 1).unwrap();
+    assert_eq!(milestone.deadline, Some(5000));
+    assert!(!milestone.late);
+
+    let content_hash = BytesN::from_array(&env, &[4u8; 32]);
+    contract.submit_deliverable_by_id(&DEFAULT_ESCROW_ID, &freelancer, &on_time, &content_hash, &String::from_str(&env, ""));
+
+    env.ledger().with_mut(|l| l.timestamp = 5001);
+    contract.submit_deliverable_by_id(&DEFAULT_ESCROW_ID, &freelancer, &late, &content_hash, &String::from_str(&env, ""));
+
+    let milestones = contract.get_milestones();
+    assert!(!milestones.get(on_time - 1).unwrap().late);
+    assert!(milestones.get(late - 1).unwrap().late);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #12)")]
+fn test_milestone_deadline_must_be_in_future() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (contract, client, _freelancer, _milestone_id) = setup_funded_milestone(&env);
+
+    contract.add_milestone_by_id(&DEFAULT_ESCROW_ID, &client, &String::from_str(&env, "Backend"), &300, &Some(1000));
+}
+
+#[test]
+fn test_milestone_dispute_leaves_other_milestones_flowing() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, platform_wallet) = setup_token_and_fee_manager(&env);
+    let amount = 10_000;
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &amount);
+    let token_client = token::Client::new(&env, &token);
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &3600, &fee_manager);
+    contract.deposit_funds(&client);
+    let design = contract.add_milestone(&client, &String::from_str(&env, "Design"), &4_000);
+    let build = contract.add_milestone(&client, &String::from_str(&env, "Build"), &6_000);
+
+    contract.dispute_milestone_by_id(&DEFAULT_ESCROW_ID, &client, &build);
+    assert_eq!(contract.get_escrow_data().state, EscrowState::Funded);
+
+    // The uncontested milestone still pays out: 4000 less the 2.5% escrow fee
+    contract.approve_milestone(&client, &design);
+    contract.release_milestone(&freelancer, &design);
+    assert_eq!(token_client.balance(&freelancer), 3_900);
+
+    // Arbitrator splits only the contested 6000; both halves pay the 5% dispute fee
+    contract.resolve_milestone_dispute_by_id(&DEFAULT_ESCROW_ID, &arbitrator, &build, &Symbol::new(&env, "split"));
+
+    assert_eq!(token_client.balance(&freelancer), 3_900 + 2_850);
+    assert_eq!(token_client.balance(&client), 2_850);
+    assert_eq!(token_client.balance(&platform_wallet), 100 + 300);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    let data = contract.get_escrow_data();
+    assert_eq!(data.state, EscrowState::Completed);
+    let milestone = data.milestones.get(build - 1).unwrap();
+    assert!(milestone.released && !milestone.disputed);
+
+    let history = contract.get_milestone_history();
+    assert_eq!(history.get(history.len() - 1).unwrap().action, MilestoneAction::DisputeResolved);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #27)")]
+fn test_disputed_milestone_cannot_be_released() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (contract, client, freelancer, milestone_id) = setup_funded_milestone(&env);
+
+    contract.approve_milestone(&client, &milestone_id);
+    contract.dispute_milestone_by_id(&DEFAULT_ESCROW_ID, &client, &milestone_id);
+    contract.release_milestone(&freelancer, &milestone_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #27)")]
+fn test_release_funds_blocked_by_milestone_dispute() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (contract, _client, freelancer, milestone_id) = setup_funded_milestone(&env);
+
+    contract.dispute_milestone_by_id(&DEFAULT_ESCROW_ID, &freelancer, &milestone_id);
+    contract.release_funds(&freelancer);
+}
+
+fn team_shares(env: &Env, members: &[(Address, u32)]) -> Vec<TeamShare> {
+    let mut shares = Vec::new(env);
+    for (member, share_bps) in members.iter() {
+        shares.push_back(TeamShare { member: member.clone(), share_bps: *share_bps });
+    }
+    shares
+}
+
+#[test]
+fn test_team_escrow_splits_release_by_share() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let lead = Address::generate(&env);
+    let designer = Address::generate(&env);
+    let tester = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, platform_wallet) = setup_token_and_fee_manager(&env);
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &10_000);
+    let token_client = token::Client::new(&env, &token);
+
+    let shares = team_shares(&env, &[(lead.clone(), 6_000), (designer.clone(), 3_000), (tester.clone(), 1_000)]);
+    let escrow_id = contract.create_team_escrow(&client, &shares, &arbitrator, &token, &10_000, &3600, &fee_manager);
+    assert_eq!(contract.get_escrow_data_by_id(&escrow_id).freelancer, lead);
+
+    contract.deposit_funds_by_id(&escrow_id, &client);
+    // Any member can trigger the release; everyone is paid their share
+    contract.release_funds_by_id(&escrow_id, &designer);
+
+    assert_eq!(token_client.balance(&lead), 5_850);
+    assert_eq!(token_client.balance(&designer), 2_925);
+    assert_eq!(token_client.balance(&tester), 975);
+    assert_eq!(token_client.balance(&platform_wallet), 250);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    let status = contract.get_contract_status_by_id(&escrow_id, &contract_id);
+    assert_eq!(status.team.len(), 3);
+    let designer_entry = status.team.get(1).unwrap();
+    assert_eq!(designer_entry.member, designer);
+    assert_eq!(designer_entry.earned, 3_000);
+    assert_eq!(designer_entry.paid, 2_925);
+
+    let export = contract.export_escrow_data_by_id(&escrow_id, &tester, &String::from_str(&env, "squad"));
+    assert_eq!(export.team.get(2).unwrap().paid, 975);
+}
+
+#[test]
+fn test_team_member_dispute_splits_award_across_team() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let lead = Address::generate(&env);
+    let member = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, _platform_wallet) = setup_token_and_fee_manager(&env);
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &10_000);
+    let token_client = token::Client::new(&env, &token);
+
+    let shares = team_shares(&env, &[(lead.clone(), 7_000), (member.clone(), 3_000)]);
+    let escrow_id = contract.create_team_escrow(&client, &shares, &arbitrator, &token, &10_000, &3600, &fee_manager);
+    contract.deposit_funds_by_id(&escrow_id, &client);
+
+    contract.dispute_by_id(&escrow_id, &member);
+    contract.resolve_dispute_by_id(&escrow_id, &arbitrator, &Symbol::new(&env, "split"));
+
+    // 5000 for the team at a 5% dispute fee, split 70/30
+    assert_eq!(token_client.balance(&lead), 3_325);
+    assert_eq!(token_client.balance(&member), 1_425);
+    assert_eq!(token_client.balance(&client), 4_750);
+
+    let team = contract.get_escrow_data_by_id(&escrow_id).team;
+    assert_eq!(team.get(0).unwrap().earned, 3_500);
+    assert_eq!(team.get(1).unwrap().earned, 1_500);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #28)")]
+fn test_team_shares_must_total_100_percent() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let token = setup_token(&env);
+    let fee_manager = setup_fee_manager(&env);
+
+    let shares = team_shares(&env, &[(Address::generate(&env), 6_000), (Address::generate(&env), 3_000)]);
+    contract.create_team_escrow(&client, &shares, &arbitrator, &token, &1000, &3600, &fee_manager);
+}
+
+const DAY: u64 = 86_400;
+
+fn setup_retainer(env: &Env) -> (Setup<'_>, u32) {
+    let s = setup_parties(env, 3_000, setup_platform_fee_manager(env).0);
+
+    // Three 30-day periods of 1000, all funded up front
+    let schedule = RetainerSchedule { period_secs: 30 * DAY, period_amount: 1_000, total_periods: 3 };
+    let escrow_id = s.contract.create_retainer_escrow(&s.client, &s.freelancer, &s.arbitrator, &s.token.address, &schedule, &s.fee_manager);
+    s.contract.deposit_funds_by_id(&escrow_id, &s.client);
+    s.contract.top_up_retainer(&escrow_id, &s.client, &2);
+    assert_eq!(s.token.balance(&s.contract.address), 3_000);
+
+    (s, escrow_id)
+}
+
+#[test]
+fn test_retainer_releases_elapsed_periods() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (Setup { contract, freelancer, token: token_client, .. }, escrow_id) = setup_retainer(&env);
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 60 * DAY);
+    contract.claim_retainer_periods(&escrow_id, &freelancer);
+
+    assert_eq!(token_client.balance(&freelancer), 1_950);
+    assert_eq!(contract.get_retainer(&escrow_id).released_periods, 2);
+    assert_eq!(contract.get_escrow_data_by_id(&escrow_id).state, EscrowState::PartiallyReleased);
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 90 * DAY);
+    contract.claim_retainer_periods(&escrow_id, &freelancer);
+
+    assert_eq!(token_client.balance(&freelancer), 2_925);
+    assert_eq!(token_client.balance(&contract.address), 0);
+    assert_eq!(contract.get_escrow_data_by_id(&escrow_id).state, EscrowState::Completed);
+}
+
+#[test]
+fn test_retainer_limits_apply_to_total_commitment() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let s = setup_parties(&env, 2_000, setup_platform_fee_manager(&env).0);
+    s.contract.initialize_contract(&s.admin);
+    s.contract.set_config(&s.admin, &limits_config());
+
+    // Each period is below the 1000 minimum, the 2000 total is within limits
+    let schedule = RetainerSchedule { period_secs: 7 * DAY, period_amount: 500, total_periods: 4 };
+    let escrow_id = s.contract.create_retainer_escrow(&s.client, &s.freelancer, &s.arbitrator, &s.token.address, &schedule, &s.fee_manager);
+    assert_eq!(s.contract.get_retainer(&escrow_id).period_amount, 500);
+
+    // A total above the maximum is still rejected
+    let schedule = RetainerSchedule { period_secs: 7 * DAY, period_amount: 50_000, total_periods: 3 };
+    assert!(s.contract.try_create_retainer_escrow(&s.client, &s.freelancer, &s.arbitrator, &s.token.address, &schedule, &s.fee_manager).is_err());
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #30)")]
+fn test_retainer_period_not_releasable_before_it_ends() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (Setup { contract, freelancer, .. }, escrow_id) = setup_retainer(&env);
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 29 * DAY);
+    contract.claim_retainer_periods(&escrow_id, &freelancer);
+}
+
+#[test]
+fn test_retainer_objection_holds_back_period() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (Setup { contract, client, freelancer, token: token_client, .. }, escrow_id) = setup_retainer(&env);
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 40 * DAY);
+    contract.object_retainer_period(&escrow_id, &client, &BytesN::from_array(&env, &[7; 32]));
+    assert_eq!(contract.get_retainer(&escrow_id).objected_period, 2);
+
+    // Period 1 ran before the objection and still pays out; period 2 is held
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 60 * DAY);
+    contract.claim_retainer_periods(&escrow_id, &freelancer);
+    assert_eq!(token_client.balance(&freelancer), 975);
+
+    let held = contract.try_claim_retainer_periods(&escrow_id, &freelancer);
+    assert!(held.is_err());
+
+    contract.clear_retainer_objection(&escrow_id, &client);
+    contract.claim_retainer_periods(&escrow_id, &freelancer);
+    assert_eq!(token_client.balance(&freelancer), 1_950);
+}
+
+#[test]
+fn test_end_retainer_prorates_refund() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (Setup { contract, client, freelancer, token: token_client, .. }, escrow_id) = setup_retainer(&env);
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 45 * DAY);
+    contract.end_retainer(&escrow_id, &client);
+
+    // Half of period 2 is paid for, the unused 1.5 periods go back untouched
+    assert_eq!(token_client.balance(&freelancer), 1_463);
+    assert_eq!(token_client.balance(&client), 1_500);
+    assert_eq!(token_client.balance(&contract.address), 0);
+    assert_eq!(contract.get_escrow_data_by_id(&escrow_id).state, EscrowState::Refunded);
+}
+
+#[test]
+fn test_end_retainer_holds_back_running_period() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let s = setup_parties(&env, 3_000, setup_platform_fee_manager(&env).0);
+    s.contract.initialize_contract(&s.admin);
+    let mut config = limits_config();
+    config.holdback_bps = 1000;
+    config.holdback_secs = DAY;
+    s.contract.set_config(&s.admin, &config);
+
+    let schedule = RetainerSchedule { period_secs: 30 * DAY, period_amount: 1_000, total_periods: 3 };
+    let escrow_id = s.contract.create_retainer_escrow(&s.client, &s.freelancer, &s.arbitrator, &s.token.address, &schedule, &s.fee_manager);
+    s.contract.deposit_funds_by_id(&escrow_id, &s.client);
+    s.contract.top_up_retainer(&escrow_id, &s.client, &2);
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 45 * DAY);
+    s.contract.end_retainer(&escrow_id, &s.client);
+
+    // Period 1 ran in full; 10% of the 500 earned in period 2 waits out the grace window
+    assert_eq!(s.contract.get_holdback_by_id(&escrow_id).amount, 50);
+    assert_eq!(s.token.balance(&s.client), 1_500);
+    assert_eq!(s.token.balance(&s.contract.address), 50);
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 46 * DAY);
+    s.contract.release_holdback_by_id(&escrow_id);
+    assert_eq!(s.token.balance(&s.contract.address), 0);
+}
+
+#[test]
+fn test_retainer_term_may_exceed_a_year() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let s = setup_parties(&env, 24_000, setup_platform_fee_manager(&env).0);
+
+    // Two years of monthly periods
+    let schedule = RetainerSchedule { period_secs: 30 * DAY, period_amount: 1_000, total_periods: 24 };
+    let escrow_id = s.contract.create_retainer_escrow(&s.client, &s.freelancer, &s.arbitrator, &s.token.address, &schedule, &s.fee_manager);
+    assert_eq!(s.contract.get_escrow_data_by_id(&escrow_id).timeout_secs, Some(720 * DAY));
+
+    s.contract.deposit_funds_by_id(&escrow_id, &s.client);
+    s.contract.top_up_retainer(&escrow_id, &s.client, &23);
+    assert_eq!(s.token.balance(&s.contract.address), 24_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #6)")]
+fn test_retainer_rejects_full_release() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (Setup { contract, freelancer, .. }, escrow_id) = setup_retainer(&env);
+
+    contract.release_funds_by_id(&escrow_id, &freelancer);
+}
+
+fn setup_stream(env: &Env) -> (Setup<'_>, u32) {
+    let s = setup_parties(env, 10_000, setup_platform_fee_manager(env).0);
+
+    // 10_000 vesting over ten days starting now
+    let schedule = StreamSchedule { start_at: 1000, end_at: 1000 + 10 * DAY };
+    let escrow_id = s.contract.create_stream_escrow(&s.client, &s.freelancer, &s.arbitrator, &s.token.address, &10_000, &schedule, &s.fee_manager);
+    s.contract.deposit_funds_by_id(&escrow_id, &s.client);
+
+    (s, escrow_id)
+}
+
+#[test]
+fn test_stream_vests_linearly() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (Setup { contract, freelancer, token: token_client, .. }, escrow_id) = setup_stream(&env);
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 4 * DAY);
+    contract.withdraw_vested(&escrow_id, &freelancer);
+    assert_eq!(token_client.balance(&freelancer), 3_900);
+
+    let status = contract.get_contract_status_by_id(&escrow_id, &contract.address);
+    assert_eq!(status.vested_amount, 4_000);
+    assert_eq!(status.released_amount, 4_000);
+    assert_eq!(status.status, String::from_str(&env, "PartiallyReleased"));
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 12 * DAY);
+    contract.withdraw_vested(&escrow_id, &freelancer);
+    assert_eq!(token_client.balance(&freelancer), 9_750);
+    assert_eq!(token_client.balance(&contract.address), 0);
+    assert_eq!(contract.get_escrow_data_by_id(&escrow_id).state, EscrowState::Completed);
+}
+
+#[test]
+fn test_paused_stream_stops_vesting() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (Setup { contract, client, .. }, escrow_id) = setup_stream(&env);
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 2 * DAY);
+    contract.pause_stream(&escrow_id, &client);
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 5 * DAY);
+    let status = contract.get_contract_status_by_id(&escrow_id, &contract.address);
+    assert!(status.stream_paused);
+    assert_eq!(status.vested_amount, 2_000);
+
+    contract.resume_stream(&escrow_id, &client);
+    assert_eq!(contract.get_stream(&escrow_id).paused_secs, 3 * DAY);
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 6 * DAY);
+    let status = contract.get_contract_status_by_id(&escrow_id, &contract.address);
+    assert!(!status.stream_paused);
+    assert_eq!(status.vested_amount, 3_000);
+}
+
+#[test]
+fn test_cancel_stream_refunds_unvested() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (Setup { contract, client, freelancer, token: token_client, .. }, escrow_id) = setup_stream(&env);
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 3 * DAY);
+    contract.cancel_stream(&escrow_id, &client);
+
+    assert_eq!(token_client.balance(&freelancer), 2_925);
+    assert_eq!(token_client.balance(&client), 7_000);
+    assert_eq!(token_client.balance(&contract.address), 0);
+    assert_eq!(contract.get_escrow_data_by_id(&escrow_id).state, EscrowState::Refunded);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #33)")]
+fn test_withdraw_vested_with_nothing_accrued() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (Setup { contract, freelancer, .. }, escrow_id) = setup_stream(&env);
+
+    contract.withdraw_vested(&escrow_id, &freelancer);
+}
+
+#[test]
+fn test_future_stream_accepts_funding_until_it_ends() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, freelancer, arbitrator, token: token_client, fee_manager, .. } =
+        setup_parties(&env, 10_000, setup_platform_fee_manager(&env).0);
+
+    // Ten-day stream that only starts in 200 days
+    let schedule = StreamSchedule { start_at: 1000 + 200 * DAY, end_at: 1000 + 210 * DAY };
+    let escrow_id = contract.create_stream_escrow(&client, &freelancer, &arbitrator, &token_client.address, &10_000, &schedule, &fee_manager);
+
+    let data = contract.get_escrow_data_by_id(&escrow_id);
+    assert_eq!(data.created_at + data.timeout_secs.unwrap(), schedule.end_at);
+
+    env.ledger().with_mut(|l| l.timestamp = schedule.start_at - DAY);
+    contract.deposit_funds_by_id(&escrow_id, &client);
+    assert_eq!(token_client.balance(&contract.address), 10_000);
+    assert_eq!(contract.get_escrow_data_by_id(&escrow_id).state, EscrowState::Funded);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #12)")]
+fn test_stream_ending_past_max_age_rejected() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, freelancer, arbitrator, token: token_client, fee_manager, .. } =
+        setup_parties(&env, 10_000, setup_platform_fee_manager(&env).0);
+
+    let schedule = StreamSchedule { start_at: 1000 + 400 * DAY, end_at: 1000 + 410 * DAY };
+    contract.create_stream_escrow(&client, &freelancer, &arbitrator, &token_client.address, &10_000, &schedule, &fee_manager);
+}
+
+fn amount_amendment(env: &Env, amount: i128, timeout_secs: Option<u64>) -> AmendmentTerms {
+    AmendmentTerms {
+        amount,
+        timeout_secs,
+        replace_milestones: false,
+        milestones: Vec::new(env),
+    }
+}
+
+fn setup_amendable_escrow(env: &Env) -> Setup<'_> {
+    let s = setup_parties(env, 15_000, setup_platform_fee_manager(env).0);
+
+    s.contract.init_contract_full(&s.client, &s.freelancer, &s.arbitrator, &s.token.address, &10_000, &3600, &s.fee_manager);
+    s.contract.deposit_funds(&s.client);
+
+    s
+}
+
+#[test]
+fn test_amendment_top_up_settles_on_acceptance() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, freelancer, token: token_client, .. } = setup_amendable_escrow(&env);
+
+    contract.propose_amendment_by_id(&DEFAULT_ESCROW_ID, &client, &amount_amendment(&env, 15_000, Some(7200)));
+    // The client's top-up is held while the freelancer decides
+    assert_eq!(token_client.balance(&contract.address), 15_000);
+    assert_eq!(contract.get_escrow_data().amount, 10_000);
+
+    contract.accept_amendment_by_id(&DEFAULT_ESCROW_ID, &freelancer);
+
+    let data = contract.get_escrow_data();
+    assert_eq!(data.amount, 15_000);
+    assert_eq!(data.timeout_secs, Some(7200));
+    assert!(contract.get_amendment_by_id(&DEFAULT_ESCROW_ID).is_none());
+
+    let versions = contract.get_escrow_versions_by_id(&DEFAULT_ESCROW_ID);
+    assert_eq!(versions.len(), 1);
+    let version = versions.get(0).unwrap();
+    assert_eq!(version.version, 1);
+    assert_eq!(version.previous_amount, 10_000);
+    assert_eq!(version.settled, 5_000);
+    assert_eq!(version.approved_by, freelancer);
+
+    let export = contract.export_escrow_data(&client, &String::from_str(&env, "job-1"));
+    assert_eq!(export.versions, versions);
+}
+
+#[test]
+fn test_amendment_top_up_after_funding_needs_client_signature() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let s = setup_parties(&env, 15_000, setup_platform_fee_manager(&env).0);
+    s.contract.init_contract_full(&s.client, &s.freelancer, &s.arbitrator, &s.token.address, &10_000, &3600, &s.fee_manager);
+
+    // Proposed before funding, so nothing is held for the increase
+    s.contract.propose_amendment_by_id(&DEFAULT_ESCROW_ID, &s.client, &amount_amendment(&env, 15_000, None));
+    s.contract.deposit_funds(&s.client);
+    s.contract.accept_amendment_by_id(&DEFAULT_ESCROW_ID, &s.freelancer);
+
+    // The client signs the accept call itself, not just a token transfer under it
+    let client_auth = env.auths().into_iter().find(|(address, _)| *address == s.client).unwrap();
+    match client_auth.1.function {
+        AuthorizedFunction::Contract((contract_id, function, _)) => {
+            assert_eq!(contract_id, s.contract.address);
+            assert_eq!(function, Symbol::new(&env, "accept_amendment_by_id"));
+        }
+        _ => panic!("expected a contract call"),
+    }
+    assert_eq!(s.token.balance(&s.contract.address), 15_000);
+    assert_eq!(s.contract.get_escrow_data().amount, 15_000);
+}
+
+#[test]
+fn test_amendment_reduction_refunds_client() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, freelancer, token: token_client, .. } = setup_amendable_escrow(&env);
+
+    contract.propose_amendment_by_id(&DEFAULT_ESCROW_ID, &freelancer, &amount_amendment(&env, 6_000, None));
+    contract.accept_amendment_by_id(&DEFAULT_ESCROW_ID, &client);
+
+    assert_eq!(token_client.balance(&client), 9_000);
+    assert_eq!(token_client.balance(&contract.address), 6_000);
+    assert_eq!(contract.get_escrow_versions_by_id(&DEFAULT_ESCROW_ID).get(0).unwrap().settled, -4_000);
+}
+
+#[test]
+fn test_cancel_amendment_returns_escrowed_top_up() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, freelancer, token: token_client, .. } = setup_amendable_escrow(&env);
+
+    contract.propose_amendment_by_id(&DEFAULT_ESCROW_ID, &client, &amount_amendment(&env, 12_000, None));
+    contract.cancel_amendment_by_id(&DEFAULT_ESCROW_ID, &freelancer);
+
+    assert_eq!(token_client.balance(&client), 5_000);
+    assert_eq!(token_client.balance(&contract.address), 10_000);
+    assert_eq!(contract.get_escrow_versions_by_id(&DEFAULT_ESCROW_ID).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #3)")]
+fn test_proposer_cannot_accept_own_amendment() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, .. } = setup_amendable_escrow(&env);
+
+    contract.propose_amendment_by_id(&DEFAULT_ESCROW_ID, &client, &amount_amendment(&env, 12_000, None));
+    contract.accept_amendment_by_id(&DEFAULT_ESCROW_ID, &client);
+}
+
+#[test]
+fn test_amendment_rewrites_open_milestones() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (contract, client, freelancer, milestone_id) = setup_funded_milestone(&env);
+    contract.add_milestone(&client, &String::from_str(&env, "Backend"), &300);
+    contract.approve_milestone(&client, &milestone_id);
+
+    let mut milestones = Vec::new(&env);
+    milestones.push_back(MilestoneTerms { description: String::from_str(&env, "Landing page"), amount: 400, deadline: None });
+    milestones.push_back(MilestoneTerms { description: String::from_str(&env, "Backend and API"), amount: 500, deadline: None });
+    let terms = AmendmentTerms { amount: 1000, timeout_secs: None, replace_milestones: true, milestones };
+
+    contract.propose_amendment_by_id(&DEFAULT_ESCROW_ID, &freelancer, &terms);
+    contract.accept_amendment_by_id(&DEFAULT_ESCROW_ID, &client);
+
+    let milestones = contract.get_milestones();
+    assert_eq!(milestones.len(), 2);
+    assert!(milestones.get(0).unwrap().approved);
+    assert_eq!(milestones.get(1).unwrap().id, 2);
+    assert_eq!(milestones.get(1).unwrap().amount, 500);
+    let history = contract.get_milestone_history();
+    assert_eq!(history.get(history.len() - 1).unwrap().action, MilestoneAction::Amended);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #17)")]
+fn test_amendment_cannot_drop_delivered_milestone() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (contract, client, freelancer, milestone_id) = setup_funded_milestone(&env);
+    contract.approve_milestone(&client, &milestone_id);
+
+    let terms = AmendmentTerms { amount: 1000, timeout_secs: None, replace_milestones: true, milestones: Vec::new(&env) };
+    contract.propose_amendment_by_id(&DEFAULT_ESCROW_ID, &freelancer, &terms);
+}
+
+#[test]
+fn test_tip_after_release_pays_freelancer_net_of_tip_fee() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, platform_wallet) = setup_token_and_fee_manager(&env);
+    FeeManagerContractClient::new(&env, &fee_manager).set_tip_fee_rate(&100); // 1%
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &11_000);
+    let token_client = token::Client::new(&env, &token);
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &10_000, &3600, &fee_manager);
+    contract.deposit_funds(&client);
+    contract.release_funds(&freelancer);
+
+    contract.tip(&client, &1_000);
+
+    assert_eq!(token_client.balance(&freelancer), 9_750 + 990);
+    assert_eq!(token_client.balance(&platform_wallet), 250 + 10);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    // The escrow's own fee accounting is untouched by the tip
+    let data = contract.get_escrow_data();
+    assert_eq!(data.fee_collected, 250);
+    assert_eq!(data.net_amount, 9_750);
+
+    let tips = contract.get_tips_by_id(&DEFAULT_ESCROW_ID);
+    assert_eq!(tips.len(), 1);
+    let tip = tips.get(0).unwrap();
+    assert_eq!(tip.from, client);
+    assert_eq!(tip.amount, 1_000);
+    assert_eq!(tip.fee, 10);
+    assert_eq!(tip.timestamp, 1000);
+}
+
+#[test]
+fn test_tip_on_team_escrow_updates_member_totals() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let lead = Address::generate(&env);
+    let designer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, _) = setup_token_and_fee_manager(&env);
+    FeeManagerContractClient::new(&env, &fee_manager).set_tip_fee_rate(&100); // 1%
+    token::StellarAssetClient::new(&env, &token).mint(&client, &11_000);
+
+    let shares = team_shares(&env, &[(lead.clone(), 7_000), (designer.clone(), 3_000)]);
+    let escrow_id = contract.create_team_escrow(&client, &shares, &arbitrator, &token, &10_000, &3600, &fee_manager);
+    contract.deposit_funds_by_id(&escrow_id, &client);
+    contract.release_funds_by_id(&escrow_id, &lead);
+
+    contract.tip_by_id(&escrow_id, &client, &1_000);
+
+    let data = contract.get_escrow_data_by_id(&escrow_id);
+    let designer_entry = data.team.get(1).unwrap();
+    assert_eq!(designer_entry.earned, 3_000 + 300);
+    assert_eq!(designer_entry.paid, 2_925 + 297);
+    let lead_entry = data.team.get(0).unwrap();
+    assert_eq!(lead_entry.earned, 7_000 + 700);
+    assert_eq!(lead_entry.paid, 6_825 + 693);
+    assert_eq!(data.fee_collected, 250);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #6)")]
+fn test_tip_rejected_before_release() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let (token, fee_manager, _) = setup_token_and_fee_manager(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&client, &11_000);
+
+    contract.init_contract_full(&client, &freelancer, &arbitrator, &token, &10_000, &3600, &fee_manager);
+    contract.deposit_funds(&client);
+
+    contract.tip(&client, &1_000);
+}
+
+#[test]
+fn test_deposit_draws_on_client_allowance() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let (token, fee_manager, _) = setup_token_and_fee_manager(&env);
+    let amount = 10_000;
+
+    token::StellarAssetClient::new(&env, &token).mint(&client, &amount);
+    let token_client = token::Client::new(&env, &token);
+
+    // The client pre-approves the escrow instead of signing the transfer itself
+    token_client.approve(&client, &contract_id, &amount, &1000);
+
+    contract.init_contract(&client, &freelancer, &token, &amount, &fee_manager);
+    contract.deposit_funds(&client);
+
+    assert_eq!(token_client.balance(&contract_id), amount);
+    assert_eq!(token_client.balance(&client), 0);
+    assert_eq!(token_client.allowance(&client, &contract_id), 0);
+    assert_eq!(contract.get_escrow_data().token, Some(token));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #36)")]
+fn test_init_contract_rejects_non_token_address() {
+    let env = setup_env();
+    env.mock_all_auths();
+
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(&env, &contract_id);
+
+    let client = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let not_a_token = setup_fee_manager(&env);
+    let fee_manager = setup_fee_manager(&env);
+
+    contract.init_contract(&client, &freelancer, &not_a_token, &1000, &fee_manager);
+}
+
+// Real SAC escrow of 10_000 initialised but not yet funded (zero-fee fee manager)
+fn setup_tranche_escrow(env: &Env) -> Setup<'_> {
+    let s = setup_parties(env, 10_000, setup_fee_manager(env));
+
+    s.contract.init_contract(&s.client, &s.freelancer, &s.token.address, &10_000, &s.fee_manager);
+    s
+}
+
+#[test]
+fn test_incremental_deposits_fund_escrow() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, token, .. } = setup_tranche_escrow(&env);
+
+    contract.deposit_by_id(&DEFAULT_ESCROW_ID, &client, &4_000);
+    let data = contract.get_escrow_data();
+    assert_eq!(data.state, EscrowState::PartiallyFunded);
+    assert_eq!(data.funded_amount, 4_000);
+    assert_eq!(data.funded_at, Some(1000));
+
+    env.ledger().with_mut(|l| l.timestamp = 2000);
+    contract.deposit_by_id(&DEFAULT_ESCROW_ID, &client, &6_000);
+    let data = contract.get_escrow_data();
+    assert_eq!(data.state, EscrowState::Funded);
+    assert_eq!(data.funded_amount, 10_000);
+    // The timeout clock keeps running from the first tranche
+    assert_eq!(data.funded_at, Some(1000));
+    assert_eq!(token.balance(&contract.address), 10_000);
+}
+
+#[test]
+fn test_milestones_release_from_funded_tranches() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, freelancer, token, .. } = setup_tranche_escrow(&env);
+
+    contract.deposit_by_id(&DEFAULT_ESCROW_ID, &client, &3_000);
+    let first = contract.add_milestone(&client, &String::from_str(&env, "Phase 1"), &3_000);
+    let second = contract.add_milestone(&client, &String::from_str(&env, "Phase 2"), &7_000);
+
+    contract.approve_milestone(&client, &first);
+    contract.release_milestone(&freelancer, &first);
+    assert_eq!(contract.get_escrow_data().state, EscrowState::PartiallyReleased);
+
+    // The second tranche arrives after the first milestone has already paid out
+    contract.deposit_by_id(&DEFAULT_ESCROW_ID, &client, &7_000);
+    contract.approve_milestone(&client, &second);
+    contract.release_milestone(&freelancer, &second);
+
+    let data = contract.get_escrow_data();
+    assert_eq!(data.state, EscrowState::Completed);
+    assert_eq!(data.funded_amount, 10_000);
+    assert_eq!(token.balance(&freelancer), 10_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #5)")]
+fn test_milestone_release_limited_to_funded_amount() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, freelancer, .. } = setup_tranche_escrow(&env);
+
+    contract.deposit_by_id(&DEFAULT_ESCROW_ID, &client, &3_000);
+    let milestone_id = contract.add_milestone(&client, &String::from_str(&env, "Phase 1"), &5_000);
+    contract.approve_milestone(&client, &milestone_id);
+    contract.release_milestone(&freelancer, &milestone_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #5)")]
+fn test_release_funds_requires_full_funding() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, freelancer, .. } = setup_tranche_escrow(&env);
+
+    contract.deposit_by_id(&DEFAULT_ESCROW_ID, &client, &4_000);
+    let milestone_id = contract.add_milestone(&client, &String::from_str(&env, "Phase 1"), &3_000);
+    contract.approve_milestone(&client, &milestone_id);
+    contract.release_milestone(&freelancer, &milestone_id);
+    assert_eq!(contract.get_escrow_data().state, EscrowState::PartiallyReleased);
+
+    // Only 1_000 of the funded 4_000 is left, but the contract is for 10_000
+    contract.release_funds(&freelancer);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #5)")]
+fn test_auto_release_requires_full_funding() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, .. } = setup_tranche_escrow(&env);
+
+    contract.deposit_by_id(&DEFAULT_ESCROW_ID, &client, &4_000);
+    env.ledger().with_mut(|l| l.timestamp = 1000 + REFUND_WINDOW_SECS + 1);
+    contract.auto_release();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #4)")]
+fn test_deposit_cannot_exceed_escrow_amount() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, .. } = setup_tranche_escrow(&env);
+
+    contract.deposit_by_id(&DEFAULT_ESCROW_ID, &client, &6_000);
+    contract.deposit_by_id(&DEFAULT_ESCROW_ID, &client, &5_000);
+}
+
+// Funded hash-locked escrow of 5_000 with a one-day timelock, locked to sha256(key)
+fn setup_htlc<'a>(env: &'a Env, key: &Bytes) -> (EscrowContractClient<'a>, Address, Address, Address, u32) {
+    let contract_id = env.register(EscrowContract, ());
+    let contract = EscrowContractClient::new(env, &contract_id);
+
+    let client = Address::generate(env);
+    let freelancer = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(token_admin).address();
+    token::StellarAssetClient::new(env, &token).mint(&client, &5_000);
+
+    let hash_lock: BytesN<32> = env.crypto().sha256(key).into();
+    let escrow_id = contract.create_htlc_escrow(
+        &freelancer,
+        &client,
+        &token,
+        &5_000,
+        &hash_lock,
+        &DAY,
+        &setup_fee_manager(env),
+    );
+    contract.deposit_funds_by_id(&escrow_id, &client);
+    (contract, client, freelancer, token, escrow_id)
+}
+
+#[test]
+fn test_htlc_claim_with_preimage() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let key = Bytes::from_slice(&env, b"license-key-0042");
+    let (contract, _, freelancer, token, escrow_id) = setup_htlc(&env, &key);
+
+    contract.claim_htlc(&escrow_id, &freelancer, &key);
+
+    assert_eq!(token::Client::new(&env, &token).balance(&freelancer), 5_000);
+    assert_eq!(contract.get_escrow_data_by_id(&escrow_id).state, EscrowState::Released);
+    // The client reads the delivered key back from the escrow
+    assert_eq!(contract.get_hash_lock(&escrow_id).preimage, Some(key));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #38)")]
+fn test_htlc_rejects_wrong_preimage() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let key = Bytes::from_slice(&env, b"license-key-0042");
+    let (contract, _, freelancer, _, escrow_id) = setup_htlc(&env, &key);
+
+    contract.claim_htlc(&escrow_id, &freelancer, &Bytes::from_slice(&env, b"guess"));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #39)")]
+fn test_htlc_claim_after_timelock() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let key = Bytes::from_slice(&env, b"license-key-0042");
+    let (contract, _, freelancer, _, escrow_id) = setup_htlc(&env, &key);
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + DAY);
+    contract.claim_htlc(&escrow_id, &freelancer, &key);
+}
+
+#[test]
+fn test_htlc_client_reclaims_after_timelock() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let key = Bytes::from_slice(&env, b"license-key-0042");
+    let (contract, client, _, token, escrow_id) = setup_htlc(&env, &key);
+
+    // Too early: the freelancer still has time to reveal
+    assert!(contract.try_reclaim_htlc(&escrow_id, &client).is_err());
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + DAY);
+    contract.reclaim_htlc(&escrow_id, &client);
+
+    assert_eq!(token::Client::new(&env, &token).balance(&client), 5_000);
+    assert_eq!(contract.get_escrow_data_by_id(&escrow_id).state, EscrowState::Refunded);
+}
+
+// Registers an ed25519 key for the client and returns it with a signer for approvals
+fn register_signer(env: &Env, contract: &EscrowContractClient, client: &Address) -> SigningKey {
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let public_key = BytesN::from_array(env, &signer.verifying_key().to_bytes());
+    contract.register_approval_key(client, &public_key);
+    signer
+}
+
+fn sign_approval(env: &Env, signer: &SigningKey, approval: &MilestoneApproval) -> BytesN<64> {
+    let message: std::vec::Vec<u8> = approval.clone().to_xdr(env).iter().collect();
+    BytesN::from_array(env, &signer.sign(&message).to_bytes())
+}
+
+#[test]
+fn test_relayer_submits_signed_milestone_approval() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (contract, client, _, milestone_id) = setup_funded_milestone(&env);
+    let signer = register_signer(&env, &contract, &client);
+
+    let approval = MilestoneApproval {
+        escrow: contract.address.clone(),
+        escrow_id: DEFAULT_ESCROW_ID,
+        milestone_id,
+        nonce: 0,
+        expiry: 2000,
+    };
+    let signature = sign_approval(&env, &signer, &approval);
+
+    // No authorization from the client is needed to submit the approval
+    env.set_auths(&[]);
+    contract.approve_milestone_signed_by_id(&DEFAULT_ESCROW_ID, &approval, &signature);
+
+    assert!(contract.get_milestones().get(0).unwrap().approved);
+    assert_eq!(contract.get_approval_key(&client).next_nonce, 1);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #42)")]
+fn test_signed_approval_cannot_be_replayed() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (contract, client, _, milestone_id) = setup_funded_milestone(&env);
+    let signer = register_signer(&env, &contract, &client);
+
+    let approval = MilestoneApproval {
+        escrow: contract.address.clone(),
+        escrow_id: DEFAULT_ESCROW_ID,
+        milestone_id,
+        nonce: 0,
+        expiry: 2000,
+    };
+    let signature = sign_approval(&env, &signer, &approval);
+    contract.approve_milestone_signed_by_id(&DEFAULT_ESCROW_ID, &approval, &signature);
+
+    contract.approve_milestone_signed_by_id(&DEFAULT_ESCROW_ID, &approval, &signature);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #41)")]
+fn test_expired_signed_approval_rejected() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let (contract, client, _, milestone_id) = setup_funded_milestone(&env);
+    let signer = register_signer(&env, &contract, &client);
+
+    let approval = MilestoneApproval {
+        escrow: contract.address.clone(),
+        escrow_id: DEFAULT_ESCROW_ID,
+        milestone_id,
+        nonce: 0,
+        expiry: 2000,
+    };
+    let signature = sign_approval(&env, &signer, &approval);
+
+    env.ledger().with_mut(|l| l.timestamp = 2001);
+    contract.approve_milestone_signed_by_id(&DEFAULT_ESCROW_ID, &approval, &signature);
+}
+
+#[test]
+fn test_audit_log_records_mutations() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, freelancer, .. } = setup_tranche_escrow(&env);
+
+    env.ledger().with_mut(|l| l.sequence_number = 10);
+    contract.deposit_by_id(&DEFAULT_ESCROW_ID, &client, &4_000);
+    env.ledger().with_mut(|l| l.sequence_number = 11);
+    contract.deposit_by_id(&DEFAULT_ESCROW_ID, &client, &6_000);
+    env.ledger().with_mut(|l| l.sequence_number = 12);
+    contract.release_funds(&freelancer);
+
+    let log = contract.get_audit_log_by_id(&DEFAULT_ESCROW_ID, &0, &10);
+    assert_eq!(log.len(), 4);
+    assert_eq!(log.get(0).unwrap().action, AuditAction::Created);
+
+    let first = log.get(1).unwrap();
+    assert_eq!(first.action, AuditAction::Deposited);
+    assert_eq!(first.actor, client);
+    assert_eq!(first.before, EscrowState::Created);
+    assert_eq!(first.after, EscrowState::PartiallyFunded);
+    assert_eq!(first.amount, 4_000);
+    assert_eq!(first.ledger, 10);
+
+    let second = log.get(2).unwrap();
+    assert_eq!(second.before, EscrowState::PartiallyFunded);
+    assert_eq!(second.after, EscrowState::Funded);
+    assert_eq!(second.ledger, 11);
+
+    let release = log.get(3).unwrap();
+    assert_eq!(release.action, AuditAction::Released);
+    assert_eq!(release.actor, freelancer);
+    assert_eq!(release.after, EscrowState::Released);
+    assert_eq!(release.amount, 10_000);
+
+    let export = contract.export_escrow_data(&client, &String::from_str(&env, "job-1"));
+    assert_eq!(export.audit_log, log);
+    assert_eq!(export.audit_count, 4);
+}
+
+#[test]
+fn test_export_bounds_audit_log_to_one_page() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, .. } = setup_tranche_escrow(&env);
+
+    let entry = contract.get_audit_log_by_id(&DEFAULT_ESCROW_ID, &0, &1).get(0).unwrap();
+    env.as_contract(&contract.address, || {
+        for _ in 0..MAX_AUDIT_PAGE + 10 {
+            crate::storage::append_audit_entry(&env, DEFAULT_ESCROW_ID, &entry);
+        }
+    });
+
+    let export = contract.export_escrow_data(&client, &String::from_str(&env, "job-1"));
+    assert_eq!(export.audit_log.len(), MAX_AUDIT_PAGE);
+    assert_eq!(export.audit_count, MAX_AUDIT_PAGE + 11);
+    assert_eq!(contract.get_audit_log_by_id(&DEFAULT_ESCROW_ID, &MAX_AUDIT_PAGE, &MAX_AUDIT_PAGE).len(), 11);
+}
+
+#[test]
+fn test_audit_log_pagination() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, .. } = setup_tranche_escrow(&env);
+
+    for _ in 0..4 {
+        contract.deposit_by_id(&DEFAULT_ESCROW_ID, &client, &1_000);
+    }
+    assert_eq!(contract.get_audit_count_by_id(&DEFAULT_ESCROW_ID), 5);
+
+    let page = contract.get_audit_log_by_id(&DEFAULT_ESCROW_ID, &1, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().before, EscrowState::Created);
+    assert_eq!(page.get(1).unwrap().before, EscrowState::PartiallyFunded);
+
+    assert_eq!(contract.get_audit_log_by_id(&DEFAULT_ESCROW_ID, &4, &10).len(), 1);
+    assert_eq!(contract.get_audit_log_by_id(&DEFAULT_ESCROW_ID, &5, &10).len(), 0);
+}
+
+#[test]
+fn test_failed_mutation_leaves_no_audit_entry() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, freelancer, .. } = setup_tranche_escrow(&env);
+
+    contract.deposit_by_id(&DEFAULT_ESCROW_ID, &client, &6_000);
+    assert!(contract.try_deposit_by_id(&DEFAULT_ESCROW_ID, &client, &5_000).is_err());
+    assert!(contract.try_refund_client_by_id(&DEFAULT_ESCROW_ID, &freelancer).is_err());
+
+    assert_eq!(contract.get_audit_count_by_id(&DEFAULT_ESCROW_ID), 2);
+}
+
+// Funded 10_000 escrow under a config holding back 10% for a one-day grace window
+fn setup_holdback_escrow(env: &Env) -> Setup<'_> {
+    let s = setup_parties(env, 10_000, setup_fee_manager(env));
+
+    s.contract.initialize_contract(&s.admin);
+    s.contract.init_contract_full(&s.client, &s.freelancer, &s.arbitrator, &s.token.address, &10_000, &3600, &s.fee_manager);
+    let mut config = limits_config();
+    config.holdback_bps = 1000;
+    config.holdback_secs = DAY;
+    s.contract.set_config(&s.admin, &config);
+    s.contract.deposit_funds(&s.client);
+
+    s
+}
+
+#[test]
+fn test_release_keeps_holdback_until_grace_window_ends() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, freelancer, token, .. } = setup_holdback_escrow(&env);
+
+    contract.release_funds(&freelancer);
+    assert_eq!(contract.get_escrow_data().state, EscrowState::Released);
+    assert_eq!(token.balance(&freelancer), 9_000);
+    assert_eq!(token.balance(&contract.address), 1_000);
+
+    let holdback = contract.get_holdback_by_id(&DEFAULT_ESCROW_ID);
+    assert_eq!(holdback.amount, 1_000);
+    assert_eq!(holdback.expires_at, 1000 + DAY);
+    assert!(contract.try_release_holdback_by_id(&DEFAULT_ESCROW_ID).is_err());
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + DAY);
+    contract.release_holdback_by_id(&DEFAULT_ESCROW_ID);
+    assert_eq!(token.balance(&freelancer), 10_000);
+    assert!(contract.get_holdback_by_id(&DEFAULT_ESCROW_ID).settled);
+    assert!(contract.try_release_holdback_by_id(&DEFAULT_ESCROW_ID).is_err());
+}
+
+#[test]
+fn test_holdback_claim_resolved_by_arbitrator() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, freelancer, arbitrator, token, .. } = setup_holdback_escrow(&env);
+
+    contract.release_funds(&freelancer);
+    contract.open_holdback_claim_by_id(&DEFAULT_ESCROW_ID, &client, &600, &BytesN::from_array(&env, &[7u8; 32]));
+
+    // An open claim keeps the holdback frozen past the end of the window
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 2 * DAY);
+    assert!(contract.try_release_holdback_by_id(&DEFAULT_ESCROW_ID).is_err());
+
+    contract.resolve_holdback_claim_by_id(&DEFAULT_ESCROW_ID, &arbitrator, &400);
+    assert_eq!(token.balance(&client), 400);
+    assert_eq!(token.balance(&freelancer), 9_600);
+    assert_eq!(token.balance(&contract.address), 0);
+
+    let holdback = contract.get_holdback_by_id(&DEFAULT_ESCROW_ID);
+    assert_eq!(holdback.refunded, 400);
+    assert!(holdback.settled);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #44)")]
+fn test_holdback_claim_after_grace_window() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, freelancer, .. } = setup_holdback_escrow(&env);
+
+    contract.release_funds(&freelancer);
+    env.ledger().with_mut(|l| l.timestamp = 1000 + DAY);
+    contract.open_holdback_claim_by_id(&DEFAULT_ESCROW_ID, &client, &600, &BytesN::from_array(&env, &[7u8; 32]));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #46)")]
+fn test_holdback_claim_needs_arbitrator() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, admin, client, freelancer, .. } = setup_tranche_escrow(&env);
+    contract.initialize_contract(&admin);
+    let mut config = limits_config();
+    config.holdback_bps = 1000;
+    config.holdback_secs = DAY;
+    contract.set_config(&admin, &config);
+    contract.deposit_by_id(&DEFAULT_ESCROW_ID, &client, &10_000);
+    contract.release_funds(&freelancer);
+
+    contract.open_holdback_claim_by_id(&DEFAULT_ESCROW_ID, &client, &500, &BytesN::from_array(&env, &[7u8; 32]));
+}
+
+#[test]
+fn test_auto_release_keeps_holdback() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, client, token, .. } = setup_holdback_escrow(&env);
+
+    env.ledger().with_mut(|l| l.timestamp = 1000 + 3600 + REFUND_WINDOW_SECS);
+    contract.auto_release();
+    assert_eq!(token.balance(&contract.address), 1_000);
+
+    // The grace window runs from the auto-release, not from funding
+    let holdback = contract.get_holdback_by_id(&DEFAULT_ESCROW_ID);
+    assert_eq!(holdback.expires_at, 1000 + 3600 + REFUND_WINDOW_SECS + DAY);
+    assert!(contract.try_open_holdback_claim_by_id(&DEFAULT_ESCROW_ID, &client, &2_000, &BytesN::from_array(&env, &[7u8; 32])).is_err());
+}
+
+// Rating contract that accepts completions from `contract` (if `authorize`), wired in by the escrow admin
+fn setup_rating_contract<'a>(env: &'a Env, contract: &EscrowContractClient, admin: &Address, authorize: bool) -> RatingContractClient<'a> {
+    let rating_id = env.register(RatingContract, ());
+    let rating = RatingContractClient::new(env, &rating_id);
+    let rating_admin = Address::generate(env);
+    rating.init(&rating_admin);
+    rating.set_escrow_source(&rating_admin, &contract.address, &authorize);
+    contract.set_rating_contract(admin, &rating_id);
+    rating
+}
+
+#[test]
+fn test_release_registers_completion_for_rating() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, admin, client, freelancer, .. } = setup_tranche_escrow(&env);
+    contract.initialize_contract(&admin);
+    let rating = setup_rating_contract(&env, &contract, &admin, true);
+
+    contract.deposit_by_id(&DEFAULT_ESCROW_ID, &client, &10_000);
+    contract.release_funds(&freelancer);
+
+    let record = rating.get_completion(&contract.address, &DEFAULT_ESCROW_ID);
+    assert_eq!(record.client, client);
+    assert_eq!(record.freelancer, freelancer);
+    assert_eq!(record.amount, 10_000);
+    assert_eq!(record.outcome, 0);
+    assert_eq!(rating.get_completed_contracts(&freelancer), 1);
+
+    rating.submit_completion_rating(
+        &client,
+        &contract.address,
+        &DEFAULT_ESCROW_ID,
+        &5,
+        &String::from_str(&env, "Delivered on time"),
+        &String::from_str(&env, "web"),
+    );
+    assert_eq!(rating.get_user_rating_stats(&freelancer).total_ratings, 1);
+}
+
+#[test]
+fn test_release_not_blocked_by_rating_contract() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, admin, client, freelancer, token, .. } = setup_tranche_escrow(&env);
+    contract.initialize_contract(&admin);
+    // The rating contract has not allowed this escrow to register completions
+    let rating = setup_rating_contract(&env, &contract, &admin, false);
+
+    contract.deposit_by_id(&DEFAULT_ESCROW_ID, &client, &10_000);
+    contract.release_funds(&freelancer);
+
+    assert_eq!(contract.get_escrow_data().state, EscrowState::Released);
+    assert_eq!(token.balance(&freelancer), 10_000);
+    assert!(rating.try_get_completion(&contract.address, &DEFAULT_ESCROW_ID).is_err());
+}
+
+#[test]
+fn test_dispute_resolution_registers_outcome() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, admin, client, freelancer, arbitrator, .. } = setup_holdback_escrow(&env);
+    let rating = setup_rating_contract(&env, &contract, &admin, true);
+
+    contract.dispute(&client);
+    contract.resolve_dispute(&arbitrator, &Symbol::new(&env, "client_wins"));
+
+    let record = rating.get_completion(&contract.address, &DEFAULT_ESCROW_ID);
+    assert_eq!(record.outcome, 1);
+    assert_eq!(rating.get_completed_contracts(&freelancer), 0);
+}
+
+#[test]
+fn test_rating_contract_set_by_contract_admin_only() {
+    let env = setup_env();
+    env.mock_all_auths();
+    let Setup { contract, admin, client, .. } = setup_tranche_escrow(&env);
+    let rating_id = env.register(RatingContract, ());
+
+    // Not initialized yet: there is no admin to point the hook
+    assert!(contract.try_set_rating_contract(&admin, &rating_id).is_err());
+
+    contract.initialize_contract(&admin);
+    assert!(contract.try_set_rating_contract(&client, &rating_id).is_err());
+    contract.set_rating_contract(&admin, &rating_id);
+    assert_eq!(contract.get_rating_contract(), Some(rating_id));
+}
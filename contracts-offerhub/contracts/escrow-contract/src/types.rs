@@ -1,4 +1,4 @@
-use soroban_sdk::{contracterror, contracttype, Address, String, Vec};
+use soroban_sdk::{contracterror, contracttype, Address, Bytes, BytesN, String, Vec};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -9,6 +9,11 @@ pub enum EscrowState {
     Released,
     Refunded,
     Disputed,
+    Cancelled,
+    PartiallyReleased,
+    Completed,
+    Resolved,
+    PartiallyFunded,
 }
 
 impl EscrowState {
@@ -16,14 +21,36 @@ impl EscrowState {
         use EscrowState::*;
         match (self, next) {
             (Created, Funded) => true,
+            (Created, Cancelled) => true,
+            // Funding in tranches
+            (Created, PartiallyFunded) => true,
+            (PartiallyFunded, Funded) => true,
+            // Milestone payouts
+            (Funded, PartiallyReleased) => true,
+            (Funded, Completed) => true,
+            (PartiallyReleased, Completed) => true,
+            (PartiallyFunded, PartiallyReleased) => true,
+            // Whole remaining balance released at once
             (Funded, Released) => true,
+            (PartiallyReleased, Released) => true,
             (Funded, Refunded) => true,
+            (PartiallyReleased, Refunded) => true,
+            (PartiallyFunded, Refunded) => true,
             (Funded, Disputed) => true,
-            (Disputed, Released) => true,
-            (Disputed, Refunded) => true,
+            (PartiallyReleased, Disputed) => true,
+            (PartiallyFunded, Disputed) => true,
+            (Disputed, Resolved) => true,
             _ => false,
         }
     }
+
+    /// Funded escrows that still hold part or all of their balance
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self,
+            EscrowState::Funded | EscrowState::PartiallyReleased | EscrowState::PartiallyFunded
+        )
+    }
 }
 
 #[contracttype]
@@ -36,14 +63,146 @@ pub enum DisputeResult {
     Split = 3,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DisputeResolution {
+    pub freelancer_share_bps: u32,     // Freelancer's share of the disputed balance (0-10000)
+    pub awarded_milestones: Vec<u32>,  // Milestones paid to the freelancer in full before the split
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MilestoneAction {
+    Added,
+    Submitted,
+    RevisionRequested,
+    Approved,
+    AutoApproved,
+    Released,
+    Awarded,   // Paid out by the arbitrator while resolving a dispute
+    Escalated, // Revision limit reached, escrow moved to dispute
+    Disputed,  // Milestone-scoped dispute opened; the rest of the escrow keeps flowing
+    DisputeResolved,
+    Amended,   // Terms replaced by an accepted amendment
+    Removed,   // Dropped by an accepted amendment
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AuditAction {
+    Created,
+    Deposited,
+    Released,
+    AutoReleased,
+    Refunded,
+    Cancelled,
+    Disputed,
+    DisputeResolved,
+    MilestoneAdded,
+    MilestoneApproved,
+    MilestoneReleased,
+    DeliverableSubmitted,
+    RevisionRequested,
+    MilestoneDisputed,
+    MilestoneDisputeResolved,
+    CancellationRequested,
+    AmendmentProposed,
+    AmendmentAccepted,
+    AmendmentCancelled,
+    Tipped,
+    RetainerToppedUp,
+    RetainerClaimed,
+    RetainerObjected,
+    RetainerObjectionCleared,
+    RetainerEnded,
+    StreamWithdrawn,
+    StreamPaused,
+    StreamResumed,
+    StreamCancelled,
+    HtlcClaimed,
+    HtlcReclaimed,
+    HoldbackClaimed,
+    HoldbackResolved,
+    HoldbackReleased,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct AuditEntry {
+    pub action: AuditAction,
+    pub actor: Address, // The contract itself for permissionless actions such as auto-release
+    pub before: EscrowState,
+    pub after: EscrowState,
+    pub amount: i128, // Funds moved or committed by the action, 0 when none
+    pub ledger: u32,  // Ledger sequence the mutation landed in
+    pub timestamp: u64,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct MilestoneHistory {
     pub milestone: Milestone,
-    pub action: String,
+    pub action: MilestoneAction,
+    pub timestamp: u64,
+    pub reason_hash: Option<BytesN<32>>, // Off-chain reason for revision requests and escalations
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct MilestoneTerms {
+    pub description: String,
+    pub amount: i128,
+    pub deadline: Option<u64>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct AmendmentTerms {
+    pub amount: i128,
+    pub timeout_secs: Option<u64>,       // None keeps the current timeout
+    pub replace_milestones: bool,        // false keeps the milestones untouched
+    pub milestones: Vec<MilestoneTerms>, // New milestone set, position i becomes milestone i + 1
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct AmendmentProposal {
+    pub proposed_by: Address,
+    pub proposed_at: u64,
+    pub terms: AmendmentTerms,
+    pub deposited: i128, // Top-up the client escrowed along with the proposal
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct EscrowVersion {
+    pub version: u32, // 1 for the first accepted amendment
+    pub proposed_by: Address,
+    pub approved_by: Address,
+    pub previous_amount: i128,
+    pub amount: i128,
+    pub timeout_secs: Option<u64>,
+    pub milestone_count: u32,
+    pub settled: i128, // Top-up (positive) or refund (negative) moved on acceptance
     pub timestamp: u64,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TipRecord {
+    pub from: Address,
+    pub amount: i128, // Gross tip; `fee` of it went to the platform
+    pub fee: i128,
+    pub timestamp: u64,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct CancellationRequest {
+    pub requested_by: Address,
+    pub requested_at: u64,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct Milestone {
@@ -55,16 +214,41 @@ pub struct Milestone {
     pub created_at: u64,
     pub approved_at: Option<u64>,
     pub released_at: Option<u64>,
+    pub deliverable_hash: Option<BytesN<32>>, // Content hash of the latest submitted work
+    pub deliverable_note: Option<String>,
+    pub submitted_at: Option<u64>,             // Start of the client's review window
+    pub revision_count: u32,                   // Revision rounds requested by the client
+    pub deadline: Option<u64>,                 // Optional due date for delivery
+    pub late: bool,                            // Delivered (or signed off) after the deadline
+    pub disputed: bool,                        // Frozen by a milestone-scoped dispute
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TeamShare {
+    pub member: Address,
+    pub share_bps: u32, // Payout weight; the shares of a team add up to 10000
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TeamMember {
+    pub member: Address,
+    pub share_bps: u32,
+    pub earned: i128, // Gross amount allocated to the member so far
+    pub paid: i128,   // Amount actually transferred after fees
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct EscrowData {
+    pub escrow_id: u32, // 0 for the contract's own escrow, registry ids start at 1
     pub client: Address,
     pub freelancer: Address,
     pub arbitrator: Option<Address>,
     pub token: Option<Address>,
     pub amount: i128,
+    pub funded_amount: i128, // Deposited so far; equals `amount` once fully funded
     pub state: EscrowState,
     pub dispute_result: u32,
     pub created_at: u64,
@@ -79,6 +263,81 @@ pub struct EscrowData {
     pub fee_manager: Address, // Fee manager contract address
     pub fee_collected: i128,  // Total fees collected
     pub net_amount: i128,     // Amount after fees
+    pub freelancer_share_bps: u32, // Arbitrated freelancer share of the disputed funds
+    pub freelancer_award: i128,    // Gross dispute award to the freelancer (before fees)
+    pub client_award: i128,        // Gross dispute refund to the client (before fees)
+    pub team: Vec<TeamMember>,     // Payees of a team escrow; empty when `freelancer` is the only payee
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RetainerSchedule {
+    pub period_secs: u64,
+    pub period_amount: i128, // Paid out per elapsed period
+    pub total_periods: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RetainerTerms {
+    pub period_secs: u64,
+    pub period_amount: i128,
+    pub total_periods: u32,
+    pub released_periods: u32,
+    pub objected_period: u32,               // Period held back by the client, 0 when none
+    pub objection_hash: Option<BytesN<32>>, // Off-chain reason for the objection
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct StreamSchedule {
+    pub start_at: u64,
+    pub end_at: u64,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct StreamTerms {
+    pub start_at: u64,
+    pub end_at: u64,
+    pub paused_at: Option<u64>, // Vesting is frozen while the client has the stream paused
+    pub paused_secs: u64,       // Total time spent paused; pushes the effective end back
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ApprovalKey {
+    pub public_key: BytesN<32>, // ed25519 key the client signs off-chain approvals with
+    pub next_nonce: u64,        // Nonce the next signed approval must carry
+}
+
+// Signed off-chain by the client; the signature covers the XDR encoding of this struct
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct MilestoneApproval {
+    pub escrow: Address, // The escrow contract the approval is meant for
+    pub escrow_id: u32,
+    pub milestone_id: u32,
+    pub nonce: u64,
+    pub expiry: u64, // Last ledger timestamp at which the approval can be submitted
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct HashLockTerms {
+    pub hash_lock: BytesN<32>,   // SHA-256 of the deliverable key committed by the freelancer
+    pub preimage: Option<Bytes>, // The key itself, published when the freelancer claims
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Holdback {
+    pub amount: i128,                   // Kept back from the final release
+    pub expires_at: u64,                // End of the client's grace window
+    pub claim_amount: i128,             // Open client claim against the holdback, 0 when none
+    pub claim_hash: Option<BytesN<32>>, // Hash of the client's off-chain claim evidence
+    pub refunded: i128,                 // Awarded back to the client when the claim was resolved
+    pub settled: bool,
 }
 
 #[contracttype]
@@ -91,6 +350,10 @@ pub struct ContractConfig {
     pub fee_percentage: i128,             // Fee percentage (in basis points)
     pub rate_limit_calls: u32,            // Rate limit calls per window
     pub rate_limit_window_hours: u32,     // Rate limit window in hours
+    pub review_period_secs: u64,          // Client review window before a deliverable auto-approves
+    pub max_revisions: u32,               // Revision rounds per milestone before it escalates to dispute
+    pub holdback_bps: u32,                // Share of the final release held back after release, 0 disables it
+    pub holdback_secs: u64,               // Grace window in which the client can claim against the holdback
 }
 
 #[contracttype]
@@ -100,6 +363,11 @@ pub struct EscrowDataExport {
     pub escrow_data: EscrowData,
     pub milestones: Vec<Milestone>,
     pub milestone_history: Vec<MilestoneHistory>,
+    pub team: Vec<TeamMember>,
+    pub versions: Vec<EscrowVersion>,
+    pub tips: Vec<TipRecord>,
+    pub audit_log: Vec<AuditEntry>, // First page only; page the rest with get_audit_log
+    pub audit_count: u32,
     pub export_timestamp: u64,
     pub export_version: String,
 }
@@ -110,7 +378,34 @@ pub struct EscrowSummary {
     pub client: Address,
     pub freelancer: Address,
     pub amount: i128,
+    pub funded_amount: i128,
     pub status: String,
     pub created_at: u64,
     pub milestone_count: u32,
+    pub team: Vec<TeamMember>,
+    pub released_amount: i128,
+    pub vested_amount: i128, // Streaming escrows only, 0 otherwise
+    pub stream_paused: bool,
+}
+
+// Fee manager types (mirrored from fee-manager-contract for cross-contract calls)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeCalculation {
+    pub original_amount: i128, // Original amount before fees
+    pub fee_amount: i128,      // Fee amount to be collected
+    pub net_amount: i128,      // Amount after fees
+    pub fee_percentage: i128,  // Applied fee percentage
+    pub is_premium: bool,      // Whether user has premium status
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    pub escrow_fee_percentage: i128,
+    pub dispute_fee_percentage: i128,
+    pub arbitrator_fee_percentage: i128,
+    pub admin: Address,
+    pub platform_wallet: Address, // Receives the fees withheld from payouts
+    pub initialized: bool,
 }
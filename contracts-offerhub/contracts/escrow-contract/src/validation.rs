@@ -1,5 +1,6 @@
 use crate::error::Error;
-use soroban_sdk::{Address, Env, String};
+use crate::types::TeamShare;
+use soroban_sdk::{Address, Env, String, Vec};
 
 // Validation constants
 const MAX_AMOUNT: i128 = 1_000_000_000_000; // 1 trillion stroops
@@ -7,6 +8,8 @@ const MIN_TIMEOUT_SECONDS: u64 = 3600; // 1 hour
 const MAX_TIMEOUT_SECONDS: u64 = 31_536_000; // 1 year
 const MIN_MILESTONE_DESCRIPTION_LENGTH: u32 = 5;
 const MAX_MILESTONE_DESCRIPTION_LENGTH: u32 = 200;
+const MAX_TEAM_MEMBERS: u32 = 10;
+const TEAM_SHARE_TOTAL: u32 = 10_000; // Shares are basis points of every payout
 
 /// Validate amount is positive and within limits
 pub fn validate_amount(amount: i128) -> Result<(), Error> {
@@ -30,6 +33,23 @@ pub fn validate_timeout(timeout_secs: u64) -> Result<(), Error> {
     Ok(())
 }
 
+/// Validate recurring escrow terms and return the whole term in seconds. Each period
+/// must fit the timeout range; the term itself may run past a year.
+pub fn validate_retainer_terms(
+    period_secs: u64,
+    period_amount: i128,
+    total_periods: u32,
+) -> Result<u64, Error> {
+    validate_amount(period_amount)?;
+    if total_periods == 0 {
+        return Err(Error::InvalidAmount);
+    }
+    validate_timeout(period_secs)?;
+    period_secs
+        .checked_mul(total_periods as u64)
+        .ok_or(Error::InvalidTimestamp)
+}
+
 /// Validate milestone description
 pub fn validate_milestone_description(description: &String) -> Result<(), Error> {
     let len = description.len();
@@ -42,6 +62,14 @@ pub fn validate_milestone_description(description: &String) -> Result<(), Error>
     Ok(())
 }
 
+/// Validate deliverable note (may be empty)
+pub fn validate_deliverable_note(note: &String) -> Result<(), Error> {
+    if note.len() > MAX_MILESTONE_DESCRIPTION_LENGTH {
+        return Err(Error::InvalidStatus);
+    }
+    Ok(())
+}
+
 /// Validate milestone ID
 pub fn validate_milestone_id(milestone_id: u32) -> Result<(), Error> {
     if milestone_id == 0 {
@@ -56,6 +84,34 @@ pub fn validate_address(address: &Address) -> Result<(), Error> {
     Ok(())
 }
 
+/// Validate team payout shares: distinct members, no zero weights, 100% in total
+pub fn validate_team_shares(
+    client: &Address,
+    arbitrator: &Address,
+    shares: &Vec<TeamShare>,
+) -> Result<(), Error> {
+    if shares.is_empty() || shares.len() > MAX_TEAM_MEMBERS {
+        return Err(Error::InvalidTeamShares);
+    }
+    let mut total: u32 = 0;
+    for (i, share) in shares.iter().enumerate() {
+        validate_address(&share.member)?;
+        validate_different_addresses(client, &share.member)?;
+        validate_different_addresses(arbitrator, &share.member)?;
+        if share.share_bps == 0 {
+            return Err(Error::InvalidTeamShares);
+        }
+        if shares.iter().skip(i + 1).any(|other| other.member == share.member) {
+            return Err(Error::InvalidTeamShares);
+        }
+        total = total.saturating_add(share.share_bps);
+    }
+    if total != TEAM_SHARE_TOTAL {
+        return Err(Error::InvalidTeamShares);
+    }
+    Ok(())
+}
+
 /// Validate addresses are different
 pub fn validate_different_addresses(addr1: &Address, addr2: &Address) -> Result<(), Error> {
     if addr1 == addr2 {
@@ -69,11 +125,13 @@ pub fn validate_init_contract(
     _env: &Env,
     client: &Address,
     freelancer: &Address,
+    token: &Address,
     amount: i128,
     fee_manager: &Address,
 ) -> Result<(), Error> {
     validate_address(client)?;
     validate_address(freelancer)?;
+    validate_address(token)?;
     validate_address(fee_manager)?;
     validate_different_addresses(client, freelancer)?;
     validate_amount(amount)?;
@@ -81,6 +139,7 @@ pub fn validate_init_contract(
 }
 
 /// Comprehensive validation for full contract initialization
+#[allow(clippy::too_many_arguments)]
 pub fn validate_init_contract_full(
     _env: &Env,
     client: &Address,
@@ -89,11 +148,13 @@ pub fn validate_init_contract_full(
     token: &Address,
     amount: i128,
     timeout_secs: u64,
+    fee_manager: &Address,
 ) -> Result<(), Error> {
     validate_address(client)?;
     validate_address(freelancer)?;
     validate_address(arbitrator)?;
     validate_address(token)?;
+    validate_address(fee_manager)?;
     validate_different_addresses(client, freelancer)?;
     validate_different_addresses(client, arbitrator)?;
     validate_different_addresses(freelancer, arbitrator)?;
@@ -74,20 +74,21 @@ mod tests {
         let env = Env::default();
         let client = Address::generate(&env);
         let freelancer = Address::generate(&env);
+        let token = Address::generate(&env);
         let fee_manager = Address::generate(&env);
 
         // Valid initialization
-        assert!(validate_init_contract(&env, &client, &freelancer, 1000, &fee_manager).is_ok());
+        assert!(validate_init_contract(&env, &client, &freelancer, &token, 1000, &fee_manager).is_ok());
 
         // Same client and freelancer
         assert_eq!(
-            validate_init_contract(&env, &client, &client, 1000, &fee_manager),
+            validate_init_contract(&env, &client, &client, &token, 1000, &fee_manager),
             Err(Error::Unauthorized)
         );
 
         // Invalid amount
         assert_eq!(
-            validate_init_contract(&env, &client, &freelancer, 0, &fee_manager),
+            validate_init_contract(&env, &client, &freelancer, &token, 0, &fee_manager),
             Err(Error::InvalidAmount)
         );
     }
@@ -99,27 +100,28 @@ mod tests {
         let freelancer = Address::generate(&env);
         let arbitrator = Address::generate(&env);
         let token = Address::generate(&env);
+        let fee_manager = Address::generate(&env);
 
         // Valid initialization
         assert!(validate_init_contract_full(
-            &env, &client, &freelancer, &arbitrator, &token, 1000, 86400
+            &env, &client, &freelancer, &arbitrator, &token, 1000, 86400, &fee_manager
         ).is_ok());
 
         // Same client and freelancer
         assert_eq!(
-            validate_init_contract_full(&env, &client, &client, &arbitrator, &token, 1000, 86400),
+            validate_init_contract_full(&env, &client, &client, &arbitrator, &token, 1000, 86400, &fee_manager),
             Err(Error::Unauthorized)
         );
 
         // Invalid amount
         assert_eq!(
-            validate_init_contract_full(&env, &client, &freelancer, &arbitrator, &token, 0, 86400),
+            validate_init_contract_full(&env, &client, &freelancer, &arbitrator, &token, 0, 86400, &fee_manager),
             Err(Error::InvalidAmount)
         );
 
         // Invalid timeout
         assert_eq!(
-            validate_init_contract_full(&env, &client, &freelancer, &arbitrator, &token, 1000, 1800),
+            validate_init_contract_full(&env, &client, &freelancer, &arbitrator, &token, 1000, 1800, &fee_manager),
             Err(Error::InvalidStatus)
         );
     }
@@ -1,12 +1,14 @@
 use crate::error::handle_error;
 use crate::storage;
 use crate::types::{
-    EscrowCreateParams, MilestoneCreateParams, MilestoneCreateResult, MilestoneParams,
+    BatchItemResult, BatchItemStatus, BatchMode, EscrowCreateParams, MilestoneCreateParams,
+    MilestoneCreateResult, MilestoneParams,
 };
 
 use crate::{error::Error, types::DisputeParams};
-use soroban_sdk::{BytesN, Symbol};
+use soroban_sdk::xdr::ScErrorType;
 use soroban_sdk::{Address, Env, Vec};
+use soroban_sdk::{BytesN, IntoVal, Symbol, TryFromVal, Val};
 
 const MAX_BATCH_SIZE: u32 = 100;
 
@@ -15,6 +17,9 @@ pub fn upload_escrow_wasm(env: Env, wasm_hash: BytesN<32>) {
 }
 
 pub fn deploy_new_escrow(env: Env, create_params: EscrowCreateParams) -> Address {
+    // The escrow is initialized in the client's name, so the client has to sign for it
+    create_params.client.require_auth();
+
     // Validate amount is positive
     if create_params.amount <= 0 {
         handle_error(&env, Error::InvalidAmountSet)
@@ -33,20 +38,26 @@ pub fn deploy_new_escrow(env: Env, create_params: EscrowCreateParams) -> Address
 
     let next_escrow_id = storage::next_escrow_id(&env);
 
-    // Deploy escrow
+    // Deploy and initialize in the same invocation so a failed init reverts the deploy
     let escrow_address = env
         .deployer()
         .with_current_contract(create_params.salt)
         .deploy_v2(wasm_hash.unwrap(), ());
 
-    // TODO: Initialize escrow contract (commented out due to WASM import issues)
-    // let escrow_client = crate::escrow_contract::Client::new(&env, &escrow_address);
-    // escrow_client.init_contract(
-    //     &create_params.client,
-    //     &create_params.freelancer,
-    //     &create_params.amount,
-    //     &create_params.fee_manager,
-    // );
+    env.invoke_contract::<()>(
+        &escrow_address,
+        &Symbol::new(&env, "init_contract_full"),
+        (
+            create_params.client,
+            create_params.freelancer,
+            create_params.arbitrator,
+            create_params.token,
+            create_params.amount,
+            create_params.timeout_secs,
+            create_params.fee_manager,
+        )
+            .into_val(&env),
+    );
 
     storage::store_escrow(&env, &next_escrow_id, &escrow_address);
     storage::set_next_escrow_id(&env, next_escrow_id + 1);
@@ -73,106 +84,140 @@ pub fn batch_deploy(env: Env, params: Vec<EscrowCreateParams>) -> Vec<Address> {
     deployed_escrows
 }
 
-pub fn batch_deposit_funds(env: Env, escrow_ids: Vec<u32>, client: Address) {
+pub fn batch_deposit_funds(
+    env: Env,
+    escrow_ids: Vec<u32>,
+    client: Address,
+    mode: BatchMode,
+) -> Vec<BatchItemResult> {
     client.require_auth();
+    check_batch_size(&env, escrow_ids.len());
 
-    for escrow_id in escrow_ids.iter() {
-        let escrow_address = storage::escrow_addr_by_id(&env, escrow_id);
+    let mut results = Vec::new(&env);
 
-        if escrow_address.is_none() {
-            handle_error(&env, Error::EscrowIdNotFoundError);
-        }
-
-        let escrow_address = escrow_address.unwrap();
-
-        // TODO: Call escrow contract (commented out due to WASM import issues)
-        // let escrow_client = crate::escrow_contract::Client::new(&env, &escrow_address);
-        // escrow_client.deposit_funds(&client);
+    for escrow_id in escrow_ids.iter() {
+        let outcome = call_escrow::<()>(
+            &env,
+            &mode,
+            escrow_id,
+            "deposit_funds",
+            (client.clone(),).into_val(&env),
+        );
+        results.push_back(item_result(escrow_id, outcome));
     }
+
+    results
 }
 
-pub fn batch_release_funds(env: Env, escrow_ids: Vec<u32>, freelancer: Address) {
+pub fn batch_release_funds(
+    env: Env,
+    escrow_ids: Vec<u32>,
+    freelancer: Address,
+    mode: BatchMode,
+) -> Vec<BatchItemResult> {
     freelancer.require_auth();
+    check_batch_size(&env, escrow_ids.len());
 
-    for escrow_id in escrow_ids.iter() {
-        let escrow_address = storage::escrow_addr_by_id(&env, escrow_id);
+    let mut results = Vec::new(&env);
 
-        if escrow_address.is_none() {
-            handle_error(&env, Error::EscrowIdNotFoundError);
-        }
-
-        let escrow_address = escrow_address.unwrap();
-
-        // TODO: Call escrow contract (commented out due to WASM import issues)
-        // let escrow_client = crate::escrow_contract::Client::new(&env, &escrow_address);
-        // escrow_client.release_funds(&freelancer);
+    for escrow_id in escrow_ids.iter() {
+        let outcome = call_escrow::<()>(
+            &env,
+            &mode,
+            escrow_id,
+            "release_funds",
+            (freelancer.clone(),).into_val(&env),
+        );
+        results.push_back(item_result(escrow_id, outcome));
     }
+
+    results
 }
 
-pub fn batch_create_disputes(env: Env, escrow_ids: Vec<u32>, caller: Address) {
+pub fn batch_create_disputes(
+    env: Env,
+    escrow_ids: Vec<u32>,
+    caller: Address,
+    mode: BatchMode,
+) -> Vec<BatchItemResult> {
     caller.require_auth();
+    check_batch_size(&env, escrow_ids.len());
 
-    for escrow_id in escrow_ids.iter() {
-        let escrow_address = storage::escrow_addr_by_id(&env, escrow_id);
+    let mut results = Vec::new(&env);
 
-        if escrow_address.is_none() {
-            handle_error(&env, Error::EscrowIdNotFoundError);
-        }
-
-        let escrow_address = escrow_address.unwrap();
-
-        // TODO: Call escrow contract (commented out due to WASM import issues)
-        // let escrow_client = crate::escrow_contract::Client::new(&env, &escrow_address);
-        // escrow_client.dispute(&caller);
+    for escrow_id in escrow_ids.iter() {
+        let outcome = call_escrow::<()>(
+            &env,
+            &mode,
+            escrow_id,
+            "dispute",
+            (caller.clone(),).into_val(&env),
+        );
+        results.push_back(item_result(escrow_id, outcome));
     }
+
+    results
 }
 
-pub fn batch_resolve_disputes(env: Env, caller: Address, dispute_params: Vec<DisputeParams>) {
+pub fn batch_resolve_disputes(
+    env: Env,
+    caller: Address,
+    dispute_params: Vec<DisputeParams>,
+    mode: BatchMode,
+) -> Vec<BatchItemResult> {
     caller.require_auth();
+    check_batch_size(&env, dispute_params.len());
 
-    for param in dispute_params.iter() {
-        let escrow_address = storage::escrow_addr_by_id(&env, param.escrow_id);
-
-        if escrow_address.is_none() {
-            handle_error(&env, Error::EscrowIdNotFoundError);
-        }
-
-        let escrow_address = escrow_address.unwrap();
+    let mut results = Vec::new(&env);
 
-        // TODO: Call escrow contract (commented out due to WASM import issues)
-        // let escrow_client = crate::escrow_contract::Client::new(&env, &escrow_address);
-        // escrow_client.resolve_dispute(&caller, &param.result);
+    for param in dispute_params.iter() {
+        let outcome = call_escrow::<()>(
+            &env,
+            &mode,
+            param.escrow_id,
+            "resolve_dispute",
+            (caller.clone(), param.result).into_val(&env),
+        );
+        results.push_back(item_result(param.escrow_id, outcome));
     }
+
+    results
 }
 
 pub fn batch_add_milestones(
     env: Env,
     milestone_create_params: Vec<MilestoneCreateParams>,
     client: Address,
+    mode: BatchMode,
 ) -> Vec<MilestoneCreateResult> {
     client.require_auth();
+    check_batch_size(&env, milestone_create_params.len());
 
     let mut milestone_create_results = Vec::new(&env);
 
     for param in milestone_create_params.iter() {
-        let escrow_address = storage::escrow_addr_by_id(&env, param.escrow_id);
-
-        if escrow_address.is_none() {
-            handle_error(&env, Error::EscrowIdNotFoundError);
-        }
-
-        let escrow_address = escrow_address.unwrap();
-
-        // TODO: Call escrow contract (commented out due to WASM import issues)
-        // let escrow_client = crate::escrow_contract::Client::new(&env, &escrow_address);
-        // let milestone_id = escrow_client.add_milestone(&client, &param.desc, &param.amount);
-
-        // For now, return a dummy milestone ID
-        let milestone_id = 1;
-
-        let result = MilestoneCreateResult {
-            escrow_id: param.escrow_id,
-            milestone_id,
+        let outcome = call_escrow::<u32>(
+            &env,
+            &mode,
+            param.escrow_id,
+            "add_milestone",
+            (client.clone(), param.desc, param.amount).into_val(&env),
+        );
+
+        // milestone_id is 0 when the escrow rejected the milestone
+        let result = match outcome {
+            Ok(milestone_id) => MilestoneCreateResult {
+                escrow_id: param.escrow_id,
+                milestone_id,
+                status: BatchItemStatus::Success,
+                error_code: 0,
+            },
+            Err((status, error_code)) => MilestoneCreateResult {
+                escrow_id: param.escrow_id,
+                milestone_id: 0,
+                status,
+                error_code,
+            },
         };
 
         milestone_create_results.push_back(result);
@@ -181,44 +226,79 @@ pub fn batch_add_milestones(
     milestone_create_results
 }
 
-pub fn batch_approve_milestones(env: Env, milestone_params: Vec<MilestoneParams>, client: Address) {
+pub fn batch_approve_milestones(
+    env: Env,
+    milestone_params: Vec<MilestoneParams>,
+    client: Address,
+    mode: BatchMode,
+) -> Vec<BatchItemResult> {
     client.require_auth();
+    check_batch_size(&env, milestone_params.len());
 
-    for param in milestone_params.iter() {
-        let escrow_address = storage::escrow_addr_by_id(&env, param.escrow_id);
-
-        if escrow_address.is_none() {
-            handle_error(&env, Error::EscrowIdNotFoundError);
-        }
+    let mut results = Vec::new(&env);
 
-        let escrow_address = escrow_address.unwrap();
-
-        // TODO: Call escrow contract (commented out due to WASM import issues)
-        // let escrow_client = crate::escrow_contract::Client::new(&env, &escrow_address);
-        // escrow_client.approve_milestone(&client, &param.milestone_id);
+    for param in milestone_params.iter() {
+        let outcome = call_escrow::<()>(
+            &env,
+            &mode,
+            param.escrow_id,
+            "approve_milestone",
+            (client.clone(), param.milestone_id).into_val(&env),
+        );
+        results.push_back(item_result(param.escrow_id, outcome));
     }
+
+    results
 }
 
 pub fn batch_release_milestones(
     env: Env,
     milestone_params: Vec<MilestoneParams>,
     freelancer: Address,
-) {
+    mode: BatchMode,
+) -> Vec<BatchItemResult> {
     freelancer.require_auth();
+    check_batch_size(&env, milestone_params.len());
+
+    let mut results = Vec::new(&env);
 
     for param in milestone_params.iter() {
-        let escrow_address = storage::escrow_addr_by_id(&env, param.escrow_id);
+        let outcome = call_escrow::<()>(
+            &env,
+            &mode,
+            param.escrow_id,
+            "release_milestone",
+            (freelancer.clone(), param.milestone_id).into_val(&env),
+        );
+        results.push_back(item_result(param.escrow_id, outcome));
+    }
 
-        if escrow_address.is_none() {
-            handle_error(&env, Error::EscrowIdNotFoundError);
-        }
+    results
+}
 
-        let escrow_address = escrow_address.unwrap();
+pub fn tip(env: Env, escrow_id: u32, client: Address, amount: i128) {
+    client.require_auth();
 
-        // TODO: Call escrow contract (commented out due to WASM import issues)
-        // let escrow_client = crate::escrow_contract::Client::new(&env, &escrow_address);
-        // escrow_client.release_milestone(&freelancer, &param.milestone_id);
+    if amount <= 0 {
+        handle_error(&env, Error::InvalidAmountSet)
     }
+
+    let escrow_address = storage::escrow_addr_by_id(&env, escrow_id);
+
+    if escrow_address.is_none() {
+        handle_error(&env, Error::EscrowIdNotFoundError);
+    }
+
+    let escrow_address = escrow_address.unwrap();
+
+    // The escrow books the tip, takes the tip fee and emits `escrow_tipped`
+    env.invoke_contract::<()>(
+        &escrow_address,
+        &Symbol::new(&env, "tip"),
+        (client.clone(), amount).into_val(&env),
+    );
+
+    env.events().publish((Symbol::new(&env, "tipped_escrow"), escrow_address, client), (escrow_id, amount));
 }
 
 pub fn batch_archive_escrows(env: Env, escrow_ids: Vec<u32>) -> Vec<u32> {
@@ -281,3 +361,61 @@ pub fn is_archived(env: Env, escrow_id: Option<u32>, escrow_address: Option<Addr
 
     handle_error(&env, Error::EscrowInfoNotSet)
 }
+
+fn check_batch_size(env: &Env, len: u32) {
+    if len > MAX_BATCH_SIZE {
+        handle_error(env, Error::BatchSizeExceeded)
+    }
+}
+
+// Invokes an escrow entry point by factory id. In atomic mode any failure, an
+// unknown id included, panics and reverts the whole batch; in best-effort mode it
+// only rolls back that call and is reported as a failed status plus, when the escrow
+// itself rejected the call, its contract error code.
+// The batch caller's authorization is forwarded as-is: their signature must cover the
+// escrow sub-invocation (and any token transfer below it).
+fn call_escrow<T>(
+    env: &Env,
+    mode: &BatchMode,
+    escrow_id: u32,
+    func: &str,
+    args: Vec<Val>,
+) -> Result<T, (BatchItemStatus, u32)>
+where
+    T: TryFromVal<Env, Val>,
+{
+    let escrow_address = match storage::escrow_addr_by_id(env, escrow_id) {
+        Some(address) => address,
+        None if *mode == BatchMode::Atomic => handle_error(env, Error::EscrowIdNotFoundError),
+        None => return Err((BatchItemStatus::UnknownEscrow, 0)),
+    };
+    let func = Symbol::new(env, func);
+
+    if *mode == BatchMode::Atomic {
+        return Ok(env.invoke_contract::<T>(&escrow_address, &func, args));
+    }
+
+    match env.try_invoke_contract::<T, soroban_sdk::Error>(&escrow_address, &func, args) {
+        Ok(Ok(value)) => Ok(value),
+        Err(Ok(err)) if err.is_type(ScErrorType::Contract) => {
+            Err((BatchItemStatus::Failed, err.get_code()))
+        }
+        // Host failures (auth, missing contract, bad return value) have no contract code
+        _ => Err((BatchItemStatus::CallFailed, 0)),
+    }
+}
+
+fn item_result(escrow_id: u32, outcome: Result<(), (BatchItemStatus, u32)>) -> BatchItemResult {
+    match outcome {
+        Ok(()) => BatchItemResult {
+            escrow_id,
+            status: BatchItemStatus::Success,
+            error_code: 0,
+        },
+        Err((status, error_code)) => BatchItemResult {
+            escrow_id,
+            status,
+            error_code,
+        },
+    }
+}
@@ -7,17 +7,10 @@ mod types;
 
 use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Vec};
 use types::{
-    DisputeParams, EscrowCreateParams, EscrowData, EscrowStatus, MilestoneCreateParams,
-    MilestoneCreateResult, MilestoneParams,
+    BatchItemResult, BatchMode, DisputeParams, EscrowCreateParams, EscrowData, EscrowStatus,
+    MilestoneCreateParams, MilestoneCreateResult, MilestoneParams,
 };
 
-// TODO: Fix WASM import issues
-// mod escrow_contract {
-//     soroban_sdk::contractimport!(
-//         file = "../../target/wasm32v1-none/release/escrow_contract.wasm"
-//     );
-// }
-
 #[contract]
 pub struct EscrowFactory;
 
@@ -35,44 +28,71 @@ impl EscrowFactory {
         contract::batch_deploy(env, params)
     }
 
-    pub fn batch_deposit_funds(env: Env, escrow_ids: Vec<u32>, client: Address) {
-        contract::batch_deposit_funds(env, escrow_ids, client);
+    pub fn batch_deposit_funds(
+        env: Env,
+        escrow_ids: Vec<u32>,
+        client: Address,
+        mode: BatchMode,
+    ) -> Vec<BatchItemResult> {
+        contract::batch_deposit_funds(env, escrow_ids, client, mode)
     }
 
-    pub fn batch_release_funds(env: Env, escrow_ids: Vec<u32>, freelancer: Address) {
-        contract::batch_release_funds(env, escrow_ids, freelancer);
+    pub fn batch_release_funds(
+        env: Env,
+        escrow_ids: Vec<u32>,
+        freelancer: Address,
+        mode: BatchMode,
+    ) -> Vec<BatchItemResult> {
+        contract::batch_release_funds(env, escrow_ids, freelancer, mode)
     }
 
-    pub fn batch_create_disputes(env: Env, escrow_ids: Vec<u32>, caller: Address) {
-        contract::batch_create_disputes(env, escrow_ids, caller);
+    pub fn batch_create_disputes(
+        env: Env,
+        escrow_ids: Vec<u32>,
+        caller: Address,
+        mode: BatchMode,
+    ) -> Vec<BatchItemResult> {
+        contract::batch_create_disputes(env, escrow_ids, caller, mode)
     }
 
-    pub fn batch_resolve_disputes(env: Env, caller: Address, dispute_params: Vec<DisputeParams>) {
-        contract::batch_resolve_disputes(env, caller, dispute_params);
+    pub fn batch_resolve_disputes(
+        env: Env,
+        caller: Address,
+        dispute_params: Vec<DisputeParams>,
+        mode: BatchMode,
+    ) -> Vec<BatchItemResult> {
+        contract::batch_resolve_disputes(env, caller, dispute_params, mode)
     }
 
     pub fn batch_add_milestones(
         env: Env,
         milestone_create_params: Vec<MilestoneCreateParams>,
         client: Address,
+        mode: BatchMode,
     ) -> Vec<MilestoneCreateResult> {
-        contract::batch_add_milestones(env, milestone_create_params, client)
+        contract::batch_add_milestones(env, milestone_create_params, client, mode)
     }
 
     pub fn batch_approve_milestones(
         env: Env,
         milestone_params: Vec<MilestoneParams>,
         client: Address,
-    ) {
-        contract::batch_approve_milestones(env, milestone_params, client);
+        mode: BatchMode,
+    ) -> Vec<BatchItemResult> {
+        contract::batch_approve_milestones(env, milestone_params, client, mode)
     }
 
     pub fn batch_release_milestones(
         env: Env,
         milestone_params: Vec<MilestoneParams>,
         freelancer: Address,
-    ) {
-        contract::batch_release_milestones(env, milestone_params, freelancer);
+        mode: BatchMode,
+    ) -> Vec<BatchItemResult> {
+        contract::batch_release_milestones(env, milestone_params, freelancer, mode)
+    }
+
+    pub fn tip(env: Env, escrow_id: u32, client: Address, amount: i128) {
+        contract::tip(env, escrow_id, client, amount);
     }
 
     pub fn batch_archive_escrows(env: Env, escrow_ids: Vec<u32>) -> Vec<u32> {
@@ -1,12 +1,19 @@
 #![cfg(test)]
-use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, Vec};
+extern crate std;
+
+use soroban_sdk::{testutils::Address as _, token, vec, Address, Bytes, BytesN, Env, String, Vec};
 
 use rand::rngs::OsRng;
 use rand::RngCore;
 
-use crate::types::EscrowCreateParams;
+use crate::storage;
+use crate::types::{
+    BatchItemResult, BatchItemStatus, BatchMode, EscrowCreateParams, MilestoneParams,
+};
 use crate::EscrowFactory;
 use crate::EscrowFactoryClient;
+use escrow_contract::{EscrowContract, EscrowContractClient};
+use fee_manager_contract::{FeeManagerContract, FeeManagerContractClient};
 
 fn gen_random_bytes<const N: usize>(env: &Env) -> BytesN<N> {
     let mut rng = OsRng;
@@ -17,8 +24,50 @@ fn gen_random_bytes<const N: usize>(env: &Env) -> BytesN<N> {
     BytesN::from_array(env, &random_bytes)
 }
 
-// WASM path kept for reference if needed in future integration tests:
-// ../../../target/wasm32v1-none/release/escrow_contract.wasm
+// The deploy tests need the optimized escrow WASM the factory deploys on chain,
+// which `cargo test` does not build. They are ignored by default; build it and
+// run them with
+//   cargo build -p escrow-contract --target wasm32v1-none --release
+//   stellar contract optimize --wasm target/wasm32v1-none/release/escrow_contract.wasm
+//   cargo test -p escrow-factory -- --ignored
+const ESCROW_WASM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../target/wasm32v1-none/release/escrow_contract.optimized.wasm"
+);
+
+// The network's `contract_max_size_bytes`; an upload over it is rejected
+const MAX_CONTRACT_SIZE: usize = 128 * 1024;
+
+fn upload_escrow_wasm(env: &Env) -> BytesN<32> {
+    let wasm = std::fs::read(ESCROW_WASM)
+        .unwrap_or_else(|_| panic!("escrow WASM not found at {}; build it first", ESCROW_WASM));
+    // Instantiating the full escrow WASM is well past the default test budget
+    env.cost_estimate().budget().reset_unlimited();
+    env.deployer()
+        .upload_contract_wasm(Bytes::from_slice(env, &wasm))
+}
+
+// Create params for a deploy through the factory: real SAC token and fee manager
+fn deploy_params(env: &Env) -> EscrowCreateParams {
+    let token_admin = Address::generate(env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let fee_manager = env.register(FeeManagerContract, ());
+    FeeManagerContractClient::new(env, &fee_manager)
+        .initialize(&Address::generate(env), &Address::generate(env));
+
+    EscrowCreateParams {
+        client: Address::generate(env),
+        freelancer: Address::generate(env),
+        arbitrator: Address::generate(env),
+        token,
+        amount: 5_000,
+        timeout_secs: 86_400,
+        fee_manager,
+        salt: gen_random_bytes::<32>(env),
+    }
+}
 
 #[test]
 fn test_escrow_factory_registration() {
@@ -48,7 +97,10 @@ fn test_escrow_create_params_validation() {
     let create_params = EscrowCreateParams {
         client: client.clone(),
         freelancer: freelancer.clone(),
+        arbitrator: Address::generate(&env),
+        token: Address::generate(&env),
         amount: 1000,
+        timeout_secs: 86_400,
         fee_manager: fee_manager.clone(),
         salt,
     };
@@ -92,7 +144,10 @@ fn test_batch_operations_structure() {
         let create_params = EscrowCreateParams {
             client: Address::generate(&env),
             freelancer: Address::generate(&env),
+            arbitrator: Address::generate(&env),
+            token: Address::generate(&env),
             amount: 1000 + i * 100,
+            timeout_secs: 86_400,
             fee_manager: Address::generate(&env),
             salt: gen_random_bytes::<32>(&env),
         };
@@ -107,3 +162,393 @@ fn test_batch_operations_structure() {
     assert_eq!(batch_params.get(1).unwrap().amount, 1100);
     assert_eq!(batch_params.get(2).unwrap().amount, 1200);
 }
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #3)")]
+fn test_tip_unknown_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let dummy_wasm_hash = BytesN::from_array(&env, &[0u8; 32]);
+    let contract_id = env.register(EscrowFactory, (dummy_wasm_hash,));
+    let factory = EscrowFactoryClient::new(&env, &contract_id);
+
+    factory.tip(&42, &Address::generate(&env), &100);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #6)")]
+fn test_deploy_rejects_matching_parties() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let dummy_wasm_hash = BytesN::from_array(&env, &[0u8; 32]);
+    let contract_id = env.register(EscrowFactory, (dummy_wasm_hash,));
+    let factory = EscrowFactoryClient::new(&env, &contract_id);
+
+    let party = Address::generate(&env);
+    factory.deploy_new_escrow(&EscrowCreateParams {
+        client: party.clone(),
+        freelancer: party,
+        arbitrator: Address::generate(&env),
+        token: Address::generate(&env),
+        amount: 1000,
+        timeout_secs: 86_400,
+        fee_manager: Address::generate(&env),
+        salt: gen_random_bytes::<32>(&env),
+    });
+}
+
+// Factory with `count` escrows between one client and freelancer, each for 1_000.
+// WASM deploys aren't available in unit tests, so the escrows are registered natively
+// and indexed in the factory the way deploy_new_escrow would.
+fn setup_factory_with_escrows(
+    env: &Env,
+    count: u32,
+) -> (
+    EscrowFactoryClient<'_>,
+    Vec<Address>,
+    Address,
+    Address,
+    Address,
+) {
+    let dummy_wasm_hash = BytesN::from_array(env, &[0u8; 32]);
+    let factory_id = env.register(EscrowFactory, (dummy_wasm_hash,));
+    let factory = EscrowFactoryClient::new(env, &factory_id);
+
+    let client = Address::generate(env);
+    let freelancer = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    token::StellarAssetClient::new(env, &token).mint(&client, &(1_000 * count as i128));
+
+    let fee_manager = env.register(FeeManagerContract, ());
+    FeeManagerContractClient::new(env, &fee_manager)
+        .initialize(&Address::generate(env), &Address::generate(env));
+
+    let mut escrows = Vec::new(env);
+    for id in 0..count {
+        let escrow_id = env.register(EscrowContract, ());
+        EscrowContractClient::new(env, &escrow_id).init_contract(
+            &client,
+            &freelancer,
+            &token,
+            &1_000,
+            &fee_manager,
+        );
+        env.as_contract(&factory_id, || {
+            storage::store_escrow(env, &id, &escrow_id);
+            storage::set_next_escrow_id(env, id + 1);
+        });
+        escrows.push_back(escrow_id);
+    }
+
+    (factory, escrows, client, freelancer, token)
+}
+
+fn success(escrow_id: u32) -> BatchItemResult {
+    BatchItemResult {
+        escrow_id,
+        status: BatchItemStatus::Success,
+        error_code: 0,
+    }
+}
+
+#[test]
+fn test_batch_deposit_and_release_invoke_escrows() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, escrows, client, freelancer, token) = setup_factory_with_escrows(&env, 3);
+    let token = token::Client::new(&env, &token);
+
+    let results = factory.batch_deposit_funds(&vec![&env, 0, 1, 2], &client, &BatchMode::Atomic);
+    assert_eq!(results, vec![&env, success(0), success(1), success(2)]);
+    for escrow in escrows.iter() {
+        assert_eq!(token.balance(&escrow), 1_000);
+    }
+
+    let results =
+        factory.batch_release_funds(&vec![&env, 0, 1, 2], &freelancer, &BatchMode::Atomic);
+    assert_eq!(results, vec![&env, success(0), success(1), success(2)]);
+    assert!(token.balance(&freelancer) > 0);
+    assert_eq!(token.balance(&client), 0);
+}
+
+#[test]
+fn test_batch_reports_escrow_error_per_item() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, _, client, freelancer, token) = setup_factory_with_escrows(&env, 2);
+
+    factory.batch_deposit_funds(&vec![&env, 0], &client, &BatchMode::Atomic);
+
+    // Escrow 1 was never funded, so the escrow rejects the release with InvalidStatus
+    let results =
+        factory.batch_release_funds(&vec![&env, 0, 1], &freelancer, &BatchMode::BestEffort);
+    assert_eq!(results.get(0).unwrap(), success(0));
+    assert_eq!(
+        results.get(1).unwrap(),
+        BatchItemResult {
+            escrow_id: 1,
+            status: BatchItemStatus::Failed,
+            error_code: 6,
+        }
+    );
+    assert!(token::Client::new(&env, &token).balance(&freelancer) > 0);
+}
+
+#[test]
+fn test_batch_milestones_round_trip() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, _, client, freelancer, token) = setup_factory_with_escrows(&env, 2);
+
+    factory.batch_deposit_funds(&vec![&env, 0, 1], &client, &BatchMode::Atomic);
+
+    let mut create_params = Vec::new(&env);
+    for escrow_id in 0..2 {
+        create_params.push_back(crate::types::MilestoneCreateParams {
+            escrow_id,
+            desc: String::from_str(&env, "Payroll period"),
+            amount: 1_000,
+        });
+    }
+    let created = factory.batch_add_milestones(&create_params, &client, &BatchMode::Atomic);
+
+    let mut milestone_params = Vec::new(&env);
+    for result in created.iter() {
+        assert_eq!(result.status, BatchItemStatus::Success);
+        milestone_params.push_back(MilestoneParams {
+            escrow_id: result.escrow_id,
+            milestone_id: result.milestone_id,
+        });
+    }
+
+    let approved = factory.batch_approve_milestones(&milestone_params, &client, &BatchMode::Atomic);
+    assert_eq!(approved, vec![&env, success(0), success(1)]);
+
+    let released =
+        factory.batch_release_milestones(&milestone_params, &freelancer, &BatchMode::Atomic);
+    assert_eq!(released, vec![&env, success(0), success(1)]);
+    assert!(token::Client::new(&env, &token).balance(&freelancer) > 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #3)")]
+fn test_batch_unknown_escrow_id() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, _, client, _, _) = setup_factory_with_escrows(&env, 1);
+
+    factory.batch_deposit_funds(&vec![&env, 0, 7], &client, &BatchMode::Atomic);
+}
+
+#[test]
+fn test_best_effort_batch_skips_unknown_escrow_id() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, escrows, client, _, token) = setup_factory_with_escrows(&env, 1);
+
+    let results = factory.batch_deposit_funds(&vec![&env, 7, 0], &client, &BatchMode::BestEffort);
+    assert_eq!(
+        results,
+        vec![
+            &env,
+            BatchItemResult {
+                escrow_id: 7,
+                status: BatchItemStatus::UnknownEscrow,
+                error_code: 0,
+            },
+            success(0),
+        ]
+    );
+    assert_eq!(
+        token::Client::new(&env, &token).balance(&escrows.get(0).unwrap()),
+        1_000
+    );
+}
+
+#[test]
+fn test_atomic_batch_reverts_on_first_failure() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, escrows, client, freelancer, token) = setup_factory_with_escrows(&env, 3);
+    let token = token::Client::new(&env, &token);
+
+    factory.batch_deposit_funds(&vec![&env, 0, 2], &client, &BatchMode::Atomic);
+
+    // Escrow 1 is unfunded; the whole batch fails with the escrow's InvalidStatus
+    let result =
+        factory.try_batch_release_funds(&vec![&env, 0, 1, 2], &freelancer, &BatchMode::Atomic);
+    assert!(result.is_err());
+
+    // Escrow 0 was released before the failure, but that release was rolled back too
+    assert_eq!(token.balance(&freelancer), 0);
+    assert_eq!(token.balance(&escrows.get(0).unwrap()), 1_000);
+    assert_eq!(token.balance(&escrows.get(2).unwrap()), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #6)")]
+fn test_atomic_batch_surfaces_escrow_error() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, _, _, freelancer, _) = setup_factory_with_escrows(&env, 1);
+
+    factory.batch_release_funds(&vec![&env, 0], &freelancer, &BatchMode::Atomic);
+}
+
+#[test]
+fn test_best_effort_batch_skips_failures() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, escrows, client, freelancer, token) = setup_factory_with_escrows(&env, 3);
+    let token = token::Client::new(&env, &token);
+
+    factory.batch_deposit_funds(&vec![&env, 0, 2], &client, &BatchMode::Atomic);
+
+    let results =
+        factory.batch_release_funds(&vec![&env, 0, 1, 2], &freelancer, &BatchMode::BestEffort);
+    assert_eq!(
+        results,
+        vec![
+            &env,
+            success(0),
+            BatchItemResult {
+                escrow_id: 1,
+                status: BatchItemStatus::Failed,
+                error_code: 6,
+            },
+            success(2),
+        ]
+    );
+
+    // Both funded escrows paid out; the skipped one is untouched
+    assert_eq!(token.balance(&escrows.get(0).unwrap()), 0);
+    assert_eq!(token.balance(&escrows.get(1).unwrap()), 0);
+    assert_eq!(token.balance(&escrows.get(2).unwrap()), 0);
+    assert!(token.balance(&freelancer) > 0);
+    assert_eq!(token.balance(&client), 1_000);
+}
+
+#[test]
+fn test_best_effort_milestone_failure_reports_code() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, _, client, _, _) = setup_factory_with_escrows(&env, 2);
+
+    factory.batch_deposit_funds(&vec![&env, 0, 1], &client, &BatchMode::Atomic);
+
+    // Escrow 1 has no milestone 5, so only escrow 0's approval goes through
+    let created = factory.batch_add_milestones(
+        &vec![
+            &env,
+            crate::types::MilestoneCreateParams {
+                escrow_id: 0,
+                desc: String::from_str(&env, "Design"),
+                amount: 500,
+            },
+        ],
+        &client,
+        &BatchMode::BestEffort,
+    );
+    let milestone_id = created.get(0).unwrap().milestone_id;
+
+    let results = factory.batch_approve_milestones(
+        &vec![
+            &env,
+            MilestoneParams {
+                escrow_id: 0,
+                milestone_id,
+            },
+            MilestoneParams {
+                escrow_id: 1,
+                milestone_id: 5,
+            },
+        ],
+        &client,
+        &BatchMode::BestEffort,
+    );
+    assert_eq!(results.get(0).unwrap(), success(0));
+    assert_eq!(results.get(1).unwrap().status, BatchItemStatus::Failed);
+    assert_eq!(results.get(1).unwrap().error_code, 9);
+}
+
+#[test]
+#[ignore = "needs the optimized escrow WASM"]
+fn test_escrow_wasm_fits_network_limit() {
+    let wasm = std::fs::read(ESCROW_WASM)
+        .unwrap_or_else(|_| panic!("escrow WASM not found at {}; build it first", ESCROW_WASM));
+    assert!(
+        wasm.len() <= MAX_CONTRACT_SIZE,
+        "escrow WASM is {} bytes, over the {} byte limit",
+        wasm.len(),
+        MAX_CONTRACT_SIZE
+    );
+}
+
+#[test]
+#[ignore = "needs the optimized escrow WASM"]
+fn test_deploy_initializes_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let factory_id = env.register(EscrowFactory, (upload_escrow_wasm(&env),));
+    let factory = EscrowFactoryClient::new(&env, &factory_id);
+    let params = deploy_params(&env);
+
+    let escrow_address = factory.deploy_new_escrow(&params);
+
+    // The client signed for the escrow created in their name
+    assert_eq!(env.auths()[0].0, params.client);
+
+    let data = EscrowContractClient::new(&env, &escrow_address).get_escrow_data();
+    assert_eq!(data.client, params.client);
+    assert_eq!(data.freelancer, params.freelancer);
+    assert_eq!(data.arbitrator, Some(params.arbitrator));
+    assert_eq!(data.token, Some(params.token));
+    assert_eq!(data.amount, 5_000);
+    assert_eq!(data.timeout_secs, Some(86_400));
+    assert_eq!(data.fee_manager, params.fee_manager);
+    assert_eq!(factory.get_escrow_id_by_address(&escrow_address), Some(0));
+}
+
+#[test]
+#[ignore = "needs the optimized escrow WASM"]
+fn test_batch_deploy_initializes_each_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let factory_id = env.register(EscrowFactory, (upload_escrow_wasm(&env),));
+    let factory = EscrowFactoryClient::new(&env, &factory_id);
+    let first = deploy_params(&env);
+    let second = deploy_params(&env);
+
+    let deployed = factory.batch_deploy(&vec![&env, first.clone(), second.clone()]);
+    assert_eq!(deployed.len(), 2);
+
+    for (address, params) in deployed.iter().zip([first, second]) {
+        let data = EscrowContractClient::new(&env, &address).get_escrow_data();
+        assert_eq!(data.client, params.client);
+        assert_eq!(data.arbitrator, Some(params.arbitrator));
+        assert_eq!(data.token, Some(params.token));
+    }
+}
+
+#[test]
+#[ignore = "needs the optimized escrow WASM"]
+fn test_deploy_requires_client_auth() {
+    let env = Env::default();
+
+    let factory_id = env.register(EscrowFactory, (upload_escrow_wasm(&env),));
+    let factory = EscrowFactoryClient::new(&env, &factory_id);
+    let params = deploy_params(&env);
+
+    assert!(factory.try_deploy_new_escrow(&params).is_err());
+
+    // The same deploy goes through once the client signs
+    env.mock_all_auths();
+    factory.deploy_new_escrow(&params);
+}
@@ -5,7 +5,10 @@ use soroban_sdk::{contracttype, Address, BytesN, String, Symbol, Vec};
 pub struct EscrowCreateParams {
     pub client: Address,
     pub freelancer: Address,
+    pub arbitrator: Address,
+    pub token: Address,
     pub amount: i128,
+    pub timeout_secs: u64,
     pub fee_manager: Address,
     pub salt: BytesN<32>,
 }
@@ -30,6 +33,8 @@ pub struct MilestoneCreateParams {
 pub struct MilestoneCreateResult {
     pub escrow_id: u32,
     pub milestone_id: u32,
+    pub status: BatchItemStatus,
+    pub error_code: u32,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -39,6 +44,37 @@ pub struct MilestoneParams {
     pub milestone_id: u32,
 }
 
+/// How a batch treats a failing escrow call: `Atomic` reverts the whole batch with
+/// the escrow's error, `BestEffort` records the failure and moves on.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum BatchMode {
+    Atomic,
+    BestEffort,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum BatchItemStatus {
+    Success,
+    /// The escrow rejected the call; `error_code` holds its contract error
+    Failed,
+    /// The factory has no escrow registered under that id
+    UnknownEscrow,
+    /// The call failed in the host (auth, missing contract, bad return value)
+    CallFailed,
+}
+
+/// Outcome of one escrow call in a batch. `error_code` is the escrow's contract
+/// error code when `status` is `Failed`, and 0 otherwise.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BatchItemResult {
+    pub escrow_id: u32,
+    pub status: BatchItemStatus,
+    pub error_code: u32,
+}
+
 // Escrow contract types (duplicated for factory use)
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
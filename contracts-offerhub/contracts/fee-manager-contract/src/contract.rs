@@ -7,10 +7,11 @@ use crate::{
         DEFAULT_ESCROW_FEE_PERCENTAGE, FEE_CONFIG, FEE_HISTORY, FEE_STATS, PLATFORM_BALANCE, PREMIUM_USERS,
         CONTRACT_CONFIG, DEFAULT_PLATFORM_FEE_PERCENTAGE, DEFAULT_ESCROW_TIMEOUT_DAYS, DEFAULT_MAX_RATING_PER_DAY,
         DEFAULT_MIN_ESCROW_AMOUNT, DEFAULT_MAX_ESCROW_AMOUNT, DEFAULT_DISPUTE_TIMEOUT_HOURS,
-        DEFAULT_RATE_LIMIT_WINDOW_HOURS, DEFAULT_MAX_RATE_LIMIT_CALLS, TOTAL_FESS_COLLECTED
+        DEFAULT_RATE_LIMIT_WINDOW_HOURS, DEFAULT_MAX_RATE_LIMIT_CALLS, TOTAL_FESS_COLLECTED,
+        DEFAULT_TIP_FEE_PERCENTAGE, TIP_FEE
     },
     types::{FeeCalculation, FeeConfig, FeeRecord, FeeStats, PremiumUser, FEE_TYPE_ESCROW, FEE_TYPE_DISPUTE, PlatformStats, ContractConfig},
-    validation::{validate_initialization, validate_fee_rates, validate_fee_calculation, validate_withdrawal_amount, validate_fee_type, validate_address},
+    validation::{validate_initialization, validate_fee_rates, validate_fee_percentage, validate_fee_calculation, validate_withdrawal_amount, validate_fee_type, validate_address},
 };
 
 pub fn initialize(env: &Env, admin: Address, platform_wallet: Address) {
@@ -111,6 +112,31 @@ pub fn set_fee_rates(
     );
 }
 
+pub fn set_tip_fee_rate(env: &Env, tip_fee_percentage: i128) {
+    let fee_config: FeeConfig = env.storage().instance().get(&FEE_CONFIG).unwrap();
+
+    // Only admin can set fee rates
+    fee_config.admin.require_auth();
+
+    if let Err(e) = validate_fee_percentage(tip_fee_percentage) {
+        handle_error(env, e);
+    }
+
+    env.storage().instance().set(&TIP_FEE, &tip_fee_percentage);
+
+    env.events().publish(
+        (Symbol::new(env, "tip_fee_updated"), fee_config.admin),
+        tip_fee_percentage,
+    );
+}
+
+pub fn get_tip_fee_rate(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&TIP_FEE)
+        .unwrap_or(DEFAULT_TIP_FEE_PERCENTAGE)
+}
+
 pub fn add_premium_user(env: &Env, user: Address) {
     let fee_config: FeeConfig = env.storage().instance().get(&FEE_CONFIG).unwrap();
     fee_config.admin.require_auth();
@@ -229,6 +255,31 @@ pub fn calculate_dispute_fee(env: &Env, amount: i128, user: Address) -> FeeCalcu
     }
 }
 
+pub fn calculate_tip_fee(env: &Env, amount: i128, user: Address) -> FeeCalculation {
+    // Input validation
+    if let Err(e) = validate_fee_calculation(amount, &user) {
+        handle_error(env, e);
+    }
+
+    let is_premium = is_premium_user(env, user);
+
+    let fee_percentage = if is_premium {
+        0
+    } else {
+        get_tip_fee_rate(env)
+    };
+    let fee_amount = calculate_fee_amount(amount, fee_percentage);
+    let net_amount = amount - fee_amount;
+
+    FeeCalculation {
+        original_amount: amount,
+        fee_amount,
+        net_amount,
+        fee_percentage,
+        is_premium,
+    }
+}
+
 pub fn collect_fee(env: &Env, amount: i128, fee_type: u32, user: Address) -> i128 {
     // Input validation
     if let Err(e) = validate_fee_calculation(amount, &user) {
@@ -33,6 +33,14 @@ impl FeeManagerContract {
         );
     }
 
+    pub fn set_tip_fee_rate(env: Env, tip_fee_percentage: i128) {
+        contract::set_tip_fee_rate(&env, tip_fee_percentage);
+    }
+
+    pub fn get_tip_fee_rate(env: Env) -> i128 {
+        contract::get_tip_fee_rate(&env)
+    }
+
     pub fn add_premium_user(env: Env, user: Address) {
         contract::add_premium_user(&env, user);
     }
@@ -49,6 +57,10 @@ impl FeeManagerContract {
         contract::calculate_dispute_fee(&env, amount, user)
     }
 
+    pub fn calculate_tip_fee(env: Env, amount: i128, user: Address) -> types::FeeCalculation {
+        contract::calculate_tip_fee(&env, amount, user)
+    }
+
     pub fn collect_fee(env: Env, amount: i128, fee_type: u32, user: Address) -> i128 {
         contract::collect_fee(&env, amount, fee_type, user)
     }
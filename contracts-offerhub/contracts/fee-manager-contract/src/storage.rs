@@ -6,6 +6,7 @@ pub const PLATFORM_BALANCE: Symbol = symbol_short!("PLAT_BAL");
 pub const FEE_HISTORY: Symbol = symbol_short!("FEE_HIST");
 pub const FEE_STATS: Symbol = symbol_short!("FEE_STAT");
 pub const TOTAL_FESS_COLLECTED: Symbol = symbol_short!("FEE_TOTAL");
+pub const TIP_FEE: Symbol = symbol_short!("TIP_FEE");
 
 // Storage keys for premium users
 pub const PREMIUM_USERS: Symbol = symbol_short!("PREM_USR");
@@ -18,6 +19,7 @@ pub const CONTRACT_CONFIG: Symbol = symbol_short!("CONFIG");
 pub const DEFAULT_ESCROW_FEE_PERCENTAGE: i128 = 250; // 2.5%
 pub const DEFAULT_DISPUTE_FEE_PERCENTAGE: i128 = 500; // 5.0%
 pub const DEFAULT_ARBITRATOR_FEE_PERCENTAGE: i128 = 300; // 3.0%
+pub const DEFAULT_TIP_FEE_PERCENTAGE: i128 = 0; // Tips reach the freelancer untouched
 
 
 // Default contract configuration values
@@ -333,6 +333,37 @@ fn test_calculate_dispute_fee() {
     assert!(!fee_calc.is_premium);
 }
 
+#[test]
+fn test_calculate_tip_fee() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, FeeManagerContract);
+    let client = FeeManagerContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let platform_wallet = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&admin, &platform_wallet);
+
+    // Tips are fee-free until the admin configures a rate
+    assert_eq!(client.get_tip_fee_rate(), 0);
+    let fee_calc = client.calculate_tip_fee(&1000000, &user);
+    assert_eq!(fee_calc.fee_amount, 0);
+    assert_eq!(fee_calc.net_amount, 1000000);
+
+    env.mock_all_auths();
+    client.set_tip_fee_rate(&100); // 1%
+    assert_eq!(client.get_tip_fee_rate(), 100);
+    let fee_calc = client.calculate_tip_fee(&1000000, &user);
+    assert_eq!(fee_calc.fee_percentage, 100);
+    assert_eq!(fee_calc.fee_amount, 10000);
+    assert_eq!(fee_calc.net_amount, 990000);
+
+    client.add_premium_user(&user);
+    let fee_calc_premium = client.calculate_tip_fee(&1000000, &user);
+    assert_eq!(fee_calc_premium.fee_amount, 0);
+    assert!(fee_calc_premium.is_premium);
+}
+
 #[test]
 fn test_collect_fee() {
     let env = Env::default();
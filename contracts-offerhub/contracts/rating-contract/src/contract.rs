@@ -3,7 +3,10 @@ use crate::access::{
     transfer_admin as transfer_admin_impl,
 };
 use crate::analytics::{generate_user_rating_data, get_platform_analytics};
-use crate::events::{emit_feedback_submitted, emit_rating_stats_updated, emit_rating_submitted};
+use crate::events::{
+    emit_completion_registered, emit_feedback_submitted, emit_rating_stats_updated,
+    emit_rating_submitted,
+};
 use crate::incentives::{check_rating_incentives, claim_incentive_reward as claim_incentive_impl};
 use crate::moderation::{
     auto_moderate_feedback, moderate_feedback as moderate_feedback_impl,
@@ -17,7 +20,9 @@ use crate::storage::{
     get_user_feedback_ids, get_user_rating_history, get_user_rating_stats, increment_platform_stat,
     increment_rating_count, reset_rate_limit, save_admin, save_feedback, save_rating,
     save_rating_threshold, save_reputation_contract, save_user_rating_stats, set_rate_limit_bypass,
-    set_total_rating, 
+    set_total_rating, get_completed_contracts, get_completion, has_completion,
+    increment_completed_contracts, is_escrow_source, next_completion_number, save_completion,
+    set_escrow_source,
 };
 use crate::error::Error;
 use crate::types::{
@@ -26,7 +31,8 @@ use crate::types::{
     ContractConfig, CONTRACT_CONFIG, DEFAULT_MAX_RATING_PER_DAY, DEFAULT_MAX_FEEDBACK_LENGTH, MAX_RATING_AGE,
     DEFAULT_MIN_RATING, DEFAULT_MAX_RATING, DEFAULT_RATE_LIMIT_CALLS, DEFAULT_RATE_LIMIT_WINDOW_HOURS,
     DEFAULT_AUTO_MODERATION_ENABLED, DEFAULT_RESTRICTION_THRESHOLD, DEFAULT_WARNING_THRESHOLD,
-    DEFAULT_TOP_RATED_THRESHOLD, RatingDataExport, UserRatingSummary
+    DEFAULT_TOP_RATED_THRESHOLD, RatingDataExport, UserRatingSummary, CompletionRecord,
+    DEFAULT_RATING_WINDOW_SECS, TEN_CONTRACTS_MILESTONE,
 };
 use crate::validation::{validate_report_feedback, validate_submit_rating};
 use soroban_sdk::{log, Address, Env, IntoVal, String, Symbol, Vec, Bytes};
@@ -50,6 +56,7 @@ impl RatingContract {
             restriction_threshold: DEFAULT_RESTRICTION_THRESHOLD,
             warning_threshold: DEFAULT_WARNING_THRESHOLD,
             top_rated_threshold: DEFAULT_TOP_RATED_THRESHOLD,
+            rating_window_secs: DEFAULT_RATING_WINDOW_SECS,
         };
         env.storage().instance().set(&CONTRACT_CONFIG, &contract_config);
         
@@ -385,8 +392,8 @@ impl RatingContract {
                 );
             }
 
-            if stats.total_ratings >= 10 {
-                // Award milestone NFT for 10 completed ratings
+            if get_completed_contracts(&env, &user) >= TEN_CONTRACTS_MILESTONE {
+                // Award milestone NFT for 10 completed contracts
                 let _ = Self::try_mint_achievement_nft(
                     &env,
                     &reputation_contract,
@@ -435,13 +442,15 @@ impl RatingContract {
         user: &Address,
         nft_type: &soroban_sdk::Symbol,
     ) -> Result<(), Error> {
-        let result: Result<(), soroban_sdk::InvokeError> = env.invoke_contract(
+        // The rating contract mints as itself and must be a minter on the reputation contract
+        let result = env.try_invoke_contract::<(), soroban_sdk::Error>(
             reputation_contract,
             &soroban_sdk::symbol_short!("mint_achv"),
             soroban_sdk::vec![
                 env,
-                user.into_val(env),     // to
-                nft_type.into_val(env), // nft_type
+                env.current_contract_address().into_val(env), // caller
+                user.into_val(env),                           // to
+                nft_type.into_val(env),                       // nft_type
             ],
         );
 
@@ -489,6 +498,129 @@ impl RatingContract {
         Ok(())
     }
 
+    // Admin: allow or revoke an escrow contract registering completions
+    pub fn set_escrow_source(
+        env: Env,
+        caller: Address,
+        escrow: Address,
+        authorized: bool,
+    ) -> Result<(), Error> {
+        crate::access::check_admin(&env, &caller)?;
+        set_escrow_source(&env, &escrow, authorized);
+        Ok(())
+    }
+
+    // Called by an authorized escrow contract once an escrow is released or resolved.
+    // Opens the rating window for both parties and, unless the client won the
+    // dispute, counts the contract as completed for both of them.
+    pub fn register_completion(
+        env: Env,
+        escrow: Address,
+        escrow_id: u32,
+        client: Address,
+        freelancer: Address,
+        amount: i128,
+        outcome: u32,
+    ) -> Result<(), Error> {
+        require_auth(&escrow)?;
+        if !is_escrow_source(&env, &escrow) {
+            return Err(Error::Unauthorized);
+        }
+        if has_completion(&env, &escrow, escrow_id) {
+            return Err(Error::CompletionAlreadyRegistered);
+        }
+
+        let rating_window_secs = env
+            .storage()
+            .instance()
+            .get::<_, ContractConfig>(&CONTRACT_CONFIG)
+            .map(|c| c.rating_window_secs)
+            .unwrap_or(DEFAULT_RATING_WINDOW_SECS);
+        let now = env.ledger().timestamp();
+        let record = CompletionRecord {
+            escrow: escrow.clone(),
+            escrow_id,
+            client: client.clone(),
+            freelancer: freelancer.clone(),
+            amount,
+            outcome,
+            contract_id: Self::completion_contract_id(&env, next_completion_number(&env)),
+            completed_at: now,
+            rating_deadline: now + rating_window_secs,
+        };
+        save_completion(&env, &record);
+
+        // Outcome 1 is a dispute the client won: the work was not delivered
+        if outcome != 1 {
+            increment_completed_contracts(&env, &client);
+            let completed = increment_completed_contracts(&env, &freelancer);
+            if completed == TEN_CONTRACTS_MILESTONE {
+                if let Ok(reputation_contract) = get_reputation_contract(&env) {
+                    let _ = Self::try_mint_achievement_nft(
+                        &env,
+                        &reputation_contract,
+                        &freelancer,
+                        &soroban_sdk::symbol_short!("tencontr"),
+                    );
+                }
+            }
+        }
+
+        emit_completion_registered(
+            &env,
+            &escrow,
+            escrow_id,
+            &record.contract_id,
+            outcome,
+            record.rating_deadline,
+        );
+        Ok(())
+    }
+
+    // Either party rates the other for a registered completion while its window is open
+    #[allow(clippy::too_many_arguments)]
+    pub fn submit_completion_rating(
+        env: Env,
+        caller: Address,
+        escrow: Address,
+        escrow_id: u32,
+        rating: u32,
+        feedback: String,
+        work_category: String,
+    ) -> Result<(), Error> {
+        let record = get_completion(&env, &escrow, escrow_id)?;
+
+        let rated_user = if caller == record.client {
+            record.freelancer
+        } else if caller == record.freelancer {
+            record.client
+        } else {
+            return Err(Error::Unauthorized);
+        };
+
+        if env.ledger().timestamp() > record.rating_deadline {
+            return Err(Error::RatingWindowClosed);
+        }
+
+        Self::submit_rating(
+            env,
+            caller,
+            rated_user,
+            record.contract_id,
+            rating,
+            feedback,
+            work_category,
+        )
+    }
+
+    pub fn get_completion(env: Env, escrow: Address, escrow_id: u32) -> Result<CompletionRecord, Error> {
+        get_completion(&env, &escrow, escrow_id)
+    }
+
+    pub fn get_completed_contracts(env: Env, user: Address) -> u32 {
+        get_completed_contracts(&env, &user)
+    }
+
     pub fn get_admin(env: Env) -> Result<Address, Error> {
         Ok(get_admin(&env))
     }
@@ -537,6 +669,18 @@ impl RatingContract {
         String::from_bytes(env, &new_slice)
 }
 
+    // Ratings for a registered completion are filed under "completion-<n>"
+    fn completion_contract_id(env: &Env, n: u32) -> String {
+        let prefix = b"completion-";
+        let digits = Self::u32_to_string(env, n);
+        let len = prefix.len() + digits.len() as usize;
+
+        let mut result = [0u8; 32];
+        result[..prefix.len()].copy_from_slice(prefix);
+        digits.copy_into_slice(&mut result[prefix.len()..len]);
+        String::from_bytes(env, &result[..len])
+    }
+
     fn generate_feedback_id(env: &Env, _rating_id: &String) -> String {
         // Create a simple ID without format! macro
         String::from_str(env, "feedback_id")
@@ -667,6 +811,11 @@ fn validate_config(config: &ContractConfig) -> Result<(), Error> {
     if config.warning_threshold >= config.top_rated_threshold {
         return Err(Error::InvalidRating);
     }
+
+    // Validate rating window (1-90 days)
+    if config.rating_window_secs < 24 * 60 * 60 || config.rating_window_secs > 90 * 24 * 60 * 60 {
+        return Err(Error::InvalidRating);
+    }
     
     Ok(())
 }
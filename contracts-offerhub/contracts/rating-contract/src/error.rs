@@ -38,4 +38,8 @@ pub enum Error {
     
     TimestampTooOld = 17, 
     NoRatingsFound = 18,
+    /// The rating window opened by the escrow completion has closed
+    RatingWindowClosed = 19,
+    /// The escrow has already registered its completion
+    CompletionAlreadyRegistered = 20,
 }
@@ -135,3 +135,23 @@ pub fn emit_health_issue_detected(env: &Env, issue: &String, severity: &String)
         (issue.clone(), severity.clone()),
     );
 }
+
+pub fn emit_completion_registered(
+    env: &Env,
+    escrow: &Address,
+    escrow_id: u32,
+    contract_id: &String,
+    outcome: u32,
+    rating_deadline: u64,
+) {
+    env.events().publish(
+        (symbol_short!("complete"), symbol_short!("register")),
+        (
+            escrow.clone(),
+            escrow_id,
+            contract_id.clone(),
+            outcome,
+            rating_deadline,
+        ),
+    );
+}
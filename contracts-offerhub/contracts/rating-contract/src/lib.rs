@@ -19,7 +19,7 @@ mod error;
 pub use crate::contract::RatingContract;
 use crate::types::UserRatingSummary;
 pub use types::{
-    Feedback, HealthCheckResult, HealthStatus, Rating, RatingStats, UserRatingData,
+    CompletionRecord, Feedback, HealthCheckResult, HealthStatus, Rating, RatingStats, UserRatingData,
 };
 pub use error::Error;
 
@@ -160,6 +160,59 @@ impl Contract {
         RatingContract::set_reputation_contract(env, caller, contract_address)
     }
 
+    /// Allow or revoke an escrow contract registering completions
+    pub fn set_escrow_source(
+        env: Env,
+        caller: Address,
+        escrow: Address,
+        authorized: bool,
+    ) -> Result<(), Error> {
+        RatingContract::set_escrow_source(env, caller, escrow, authorized)
+    }
+
+    /// Register a released or resolved escrow, opening its rating window
+    pub fn register_completion(
+        env: Env,
+        escrow: Address,
+        escrow_id: u32,
+        client: Address,
+        freelancer: Address,
+        amount: i128,
+        outcome: u32,
+    ) -> Result<(), Error> {
+        RatingContract::register_completion(env, escrow, escrow_id, client, freelancer, amount, outcome)
+    }
+
+    /// Rate the other party of a registered escrow completion
+    #[allow(clippy::too_many_arguments)]
+    pub fn submit_completion_rating(
+        env: Env,
+        caller: Address,
+        escrow: Address,
+        escrow_id: u32,
+        rating: u32,
+        feedback: String,
+        work_category: String,
+    ) -> Result<(), Error> {
+        RatingContract::submit_completion_rating(
+            env,
+            caller,
+            escrow,
+            escrow_id,
+            rating,
+            feedback,
+            work_category,
+        )
+    }
+
+    pub fn get_completion(env: Env, escrow: Address, escrow_id: u32) -> Result<CompletionRecord, Error> {
+        RatingContract::get_completion(env, escrow, escrow_id)
+    }
+
+    pub fn get_completed_contracts(env: Env, user: Address) -> u32 {
+        RatingContract::get_completed_contracts(env, user)
+    }
+
     pub fn get_admin(env: Env) -> Result<Address, Error> {
         RatingContract::get_admin(env)
     }
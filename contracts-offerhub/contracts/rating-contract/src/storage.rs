@@ -1,8 +1,9 @@
 use crate::types::{
-    Feedback, FeedbackReport, IncentiveRecord, RateLimitEntry, Rating, RatingStats, UserRatingSummary,
+    CompletionRecord, Feedback, FeedbackReport, IncentiveRecord, RateLimitEntry, Rating, RatingStats, UserRatingSummary,
     RatingThreshold, ADMIN, FEEDBACK, FEEDBACK_REPORTS, INCENTIVE_RECORDS, MODERATOR,
     PLATFORM_STATS, RATE_LIMITS, RATE_LIMIT_BYPASS, RATING, RATING_THRESHOLDS, REPUTATION_CONTRACT,
-    TOTAL_RATING_COUNT, USER_RATING_STATS, USER_RESTRICTIONS,
+    TOTAL_RATING_COUNT, USER_RATING_STATS, USER_RESTRICTIONS, COMPLETED_CONTRACTS, COMPLETIONS,
+    COMPLETION_COUNT, ESCROW_SOURCES,
 };
 use crate::error::Error;
 use soroban_sdk::{Address, Env, String, Symbol, Vec, log};
@@ -231,6 +232,57 @@ pub fn get_reputation_contract(env: &Env) -> Result<Address, Error> {
         .ok_or(Error::ReputationContractNotSet)
 }
 
+// Escrow completion integration
+pub fn set_escrow_source(env: &Env, escrow: &Address, authorized: bool) {
+    let key = (ESCROW_SOURCES, escrow);
+    if authorized {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+pub fn is_escrow_source(env: &Env, escrow: &Address) -> bool {
+    let key = (ESCROW_SOURCES, escrow);
+    env.storage().persistent().get(&key).unwrap_or(false)
+}
+
+pub fn save_completion(env: &Env, record: &CompletionRecord) {
+    let key = (COMPLETIONS, record.escrow.clone(), record.escrow_id);
+    env.storage().persistent().set(&key, record);
+}
+
+pub fn get_completion(env: &Env, escrow: &Address, escrow_id: u32) -> Result<CompletionRecord, Error> {
+    let key = (COMPLETIONS, escrow.clone(), escrow_id);
+    env.storage()
+        .persistent()
+        .get(&key)
+        .ok_or(Error::ContractNotFound)
+}
+
+pub fn has_completion(env: &Env, escrow: &Address, escrow_id: u32) -> bool {
+    let key = (COMPLETIONS, escrow.clone(), escrow_id);
+    env.storage().persistent().has(&key)
+}
+
+pub fn next_completion_number(env: &Env) -> u32 {
+    let count: u32 = env.storage().instance().get(&COMPLETION_COUNT).unwrap_or(0) + 1;
+    env.storage().instance().set(&COMPLETION_COUNT, &count);
+    count
+}
+
+pub fn get_completed_contracts(env: &Env, user: &Address) -> u32 {
+    let key = (COMPLETED_CONTRACTS, user);
+    env.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn increment_completed_contracts(env: &Env, user: &Address) -> u32 {
+    let key = (COMPLETED_CONTRACTS, user);
+    let count = get_completed_contracts(env, user) + 1;
+    env.storage().persistent().set(&key, &count);
+    count
+}
+
 // User restrictions
 pub fn save_user_restriction(env: &Env, user: &Address, restriction: &String) {
     let key = (USER_RESTRICTIONS, user);
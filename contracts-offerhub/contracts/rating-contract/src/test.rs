@@ -1230,4 +1230,114 @@ fn test_submit_rating_time_too_old() {
     let cid4 = String::from_str(&env, "c4");
     client.submit_rating(&caller, &rated_user, &cid4, &5u32, &feedback, &category);
 
-}
\ No newline at end of file
+}
+fn setup_completion_source(env: &Env) -> (ContractClient<'_>, Address, Address) {
+    let contract_id = create_contract(env);
+    let client = ContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    let escrow = Address::generate(env);
+    client.init(&admin);
+    client.set_escrow_source(&admin, &escrow, &true);
+    (client, admin, escrow)
+}
+
+#[test]
+fn test_completion_opens_rating_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let (client, _, escrow) = setup_completion_source(&env);
+
+    let buyer = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    client.register_completion(&escrow, &7, &buyer, &freelancer, &5_000, &0);
+
+    let record = client.get_completion(&escrow, &7);
+    assert_eq!(record.contract_id, String::from_str(&env, "completion-1"));
+    assert_eq!(record.rating_deadline, 1_000 + 14 * 24 * 60 * 60);
+    assert_eq!(client.get_completed_contracts(&buyer), 1);
+    assert_eq!(client.get_completed_contracts(&freelancer), 1);
+
+    let feedback = String::from_str(&env, "Great work");
+    let category = String::from_str(&env, "web");
+    client.submit_completion_rating(&buyer, &escrow, &7, &5, &feedback, &category);
+    assert_eq!(client.get_user_rating_stats(&freelancer).total_ratings, 1);
+
+    let outsider = Address::generate(&env);
+    let res = client.try_submit_completion_rating(&outsider, &escrow, &7, &5, &feedback, &category);
+    assert_eq!(res, Err(Ok(Error::Unauthorized)));
+
+    env.ledger().set_timestamp(record.rating_deadline + 1);
+    let res = client.try_submit_completion_rating(&freelancer, &escrow, &7, &4, &feedback, &category);
+    assert_eq!(res, Err(Ok(Error::RatingWindowClosed)));
+
+    let res = client.try_register_completion(&escrow, &7, &buyer, &freelancer, &5_000, &0);
+    assert_eq!(res, Err(Ok(Error::CompletionAlreadyRegistered)));
+}
+
+#[test]
+fn test_completion_requires_authorized_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, escrow) = setup_completion_source(&env);
+
+    let other = Address::generate(&env);
+    let res = client.try_register_completion(
+        &other,
+        &1,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &5_000,
+        &0,
+    );
+    assert_eq!(res, Err(Ok(Error::Unauthorized)));
+
+    client.set_escrow_source(&admin, &escrow, &false);
+    let res = client.try_register_completion(
+        &escrow,
+        &1,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &5_000,
+        &0,
+    );
+    assert_eq!(res, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_client_won_dispute_not_counted_as_completed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, escrow) = setup_completion_source(&env);
+
+    let buyer = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    client.register_completion(&escrow, &1, &buyer, &freelancer, &5_000, &1);
+    client.register_completion(&escrow, &2, &buyer, &freelancer, &5_000, &3);
+
+    assert_eq!(client.get_completed_contracts(&freelancer), 1);
+    assert_eq!(client.get_completed_contracts(&buyer), 1);
+}
+
+#[test]
+fn test_tenth_completion_mints_project_milestone() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    let (client, admin, escrow) = setup_completion_source(&env);
+
+    let reputation_cid = env.register(ReputationContract, ());
+    let r_client = reputation_nft_contract::ContractClient::new(&env, &reputation_cid);
+    r_client.init(&admin);
+    r_client.add_minter(&admin, &client.address);
+    client.set_reputation_contract(&admin, &reputation_cid);
+
+    let buyer = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    for escrow_id in 1..10 {
+        client.register_completion(&escrow, &escrow_id, &buyer, &freelancer, &5_000, &0);
+    }
+    assert_eq!(r_client.try_get_owner(&1), Err(Ok(E::TokenDoesNotExist)));
+
+    client.register_completion(&escrow, &10, &buyer, &freelancer, &5_000, &0);
+    assert_eq!(r_client.get_owner(&1), freelancer);
+}
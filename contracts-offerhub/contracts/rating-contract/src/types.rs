@@ -133,6 +133,10 @@ pub const USER_RESTRICTIONS: &[u8] = &[12];
 pub const RATE_LIMITS: &[u8] = &[13];
 pub const RATE_LIMIT_BYPASS: &[u8] = &[14];
 pub const CONTRACT_CONFIG: &[u8] = &[15];
+pub const ESCROW_SOURCES: &[u8] = &[16];
+pub const COMPLETIONS: &[u8] = &[17];
+pub const COMPLETED_CONTRACTS: &[u8] = &[18];
+pub const COMPLETION_COUNT: &[u8] = &[19];
 
 // Default configuration values
 pub const DEFAULT_MAX_RATING_PER_DAY: u32 = 10;          // 10 ratings per day
@@ -142,6 +146,7 @@ pub const DEFAULT_MAX_RATING: u32 = 5;                  // Maximum rating 5
 pub const DEFAULT_RATE_LIMIT_CALLS: u32 = 5;           // 5 calls per window
 pub const DEFAULT_RATE_LIMIT_WINDOW_HOURS: u32 = 1;    // 1 hour window
 pub const DEFAULT_AUTO_MODERATION_ENABLED: bool = true; // Auto-moderation enabled
+pub const DEFAULT_RATING_WINDOW_SECS: u64 = 14 * 24 * 60 * 60; // 14 days to rate a completed escrow
 
 // Rating validation constants
 pub const MIN_RATING: u32 = 1;
@@ -157,6 +162,9 @@ pub const TOTAL_RATING_COUNT: Symbol = symbol_short!("TOTALRATE");
 
 pub const MAX_RATING_AGE: u64 = 30 * 24 * 60 * 60; // 30 days in seconds
 
+// Completed contracts a freelancer needs for the ProjectMilestone achievement
+pub const TEN_CONTRACTS_MILESTONE: u32 = 10;
+
 pub fn require_auth(address: &Address) -> Result<(), Error> {
     address.require_auth();
     Ok(())
@@ -182,6 +190,21 @@ pub struct ContractConfig {
     pub restriction_threshold: u32,       // Rating threshold for restrictions
     pub warning_threshold: u32,           // Rating threshold for warnings
     pub top_rated_threshold: u32,         // Rating threshold for top-rated status
+    pub rating_window_secs: u64,          // How long a completed escrow stays open for ratings
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompletionRecord {
+    pub escrow: Address,         // Escrow contract that registered the completion
+    pub escrow_id: u32,
+    pub client: Address,
+    pub freelancer: Address,
+    pub amount: i128,
+    pub outcome: u32,            // The escrow's dispute result: 0 none, 1 client wins, 2 freelancer wins, 3 split
+    pub contract_id: String,     // Ratings for this completion are filed under this id
+    pub completed_at: u64,
+    pub rating_deadline: u64,    // Both parties can rate each other until this timestamp
 }
 
 pub struct RatingDataExport {
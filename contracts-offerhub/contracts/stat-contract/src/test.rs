@@ -15,6 +15,13 @@ impl MockTokenContract {
         1000
     }
     pub fn transfer(_env: Env, _from: Address, _to: Address, _amount: i128) {}
+    pub fn allowance(_env: Env, _from: Address, _spender: Address) -> i128 {
+        0
+    }
+    pub fn transfer_from(_env: Env, _spender: Address, _from: Address, _to: Address, _amount: i128) {}
+    pub fn decimals(_env: Env) -> u32 {
+        7
+    }
 }
 
 #[test]
@@ -93,7 +100,19 @@ fn test_get_contract_stats() {
     let amount = 500;
     let timeout = 3600; // 1 hour (minimum allowed)
 
-    escrow_client.init_contract_full(&client, &freelancer, &arbitrator, &token, &amount, &timeout);
+    let escrow_fee_manager = env.register(fee_manager_contract::FeeManagerContract, ());
+    fee_manager_contract::FeeManagerContractClient::new(&env, &escrow_fee_manager)
+        .initialize(&admin, &Address::generate(&env));
+
+    escrow_client.init_contract_full(
+        &client,
+        &freelancer,
+        &arbitrator,
+        &token,
+        &amount,
+        &timeout,
+        &escrow_fee_manager,
+    );
 
     // Deposit funds (should increment count)
     escrow_client.deposit_funds(&client);
@@ -165,6 +184,7 @@ fn test_get_contract_stats() {
         &initiator,
         &reason,
         &escrow_contract_addr,
+        &0,
         &dispute_amount,
     );
 
@@ -174,6 +194,7 @@ fn test_get_contract_stats() {
         &initiator,
         &reason,
         &escrow_contract_addr,
+        &0,
         &dispute_amount,
     );
 